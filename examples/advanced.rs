@@ -7,7 +7,7 @@
 
 extern crate logitech_led as led;
 
-use led::{Driver, Color, Key};
+use led::{Driver, Color, Key, Bitmap, Gradient, Direction};
 use std::time::Duration;
 use std::thread::sleep;
 
@@ -19,22 +19,11 @@ fn main() {
     let white = Color::new(1.0, 1.0, 1.0);
     let black = Color::new(0.0, 0.0, 0.0);
 
-    let buffer = (0..led::BITMAP_WIDTH).flat_map(|i| {
-            let h = (i as f32) *
-                (360.0 / led::BITMAP_WIDTH as f32);
-
-            // I wish we had into_iter for arrays.
-            <[u8; 4]>::from(Color::from_hsv(h, 1.0, 1.0))
-                .iter()
-                .cloned()
-                .collect::<Vec<_>>()
-        })
-        .cycle()
-        .take(led::BITMAP_SIZE)
-        .collect::<Vec<_>>();
+    let mut bitmap = Bitmap::new();
+    bitmap.paint_gradient(&Gradient::rainbow(), Direction::Horizontal);
 
     driver.exclude_keys_from_bitmap(&[Key::Q]).unwrap();
-    driver.set_lighting_from_bitmap(&buffer[..]).unwrap();
+    driver.set_lighting_from_bitmap(&bitmap).unwrap();
     sleep(Duration::from_secs(1));
 
     driver.set_lighting_for_key(Key::W, white).unwrap();
@@ -0,0 +1,38 @@
+//! Guided calibration
+//!
+//! Shows a test pattern per step on a per-key keyboard. Type `+` or `-`
+//! and press enter to adjust, or just press enter to confirm.
+//! The result is saved to `keyboard.cal`.
+//!
+
+extern crate logitech_led as led;
+
+use led::{Driver, DeviceType};
+use led::calibration::{Routine, Step};
+use std::io::{self, BufRead};
+
+fn main() {
+    let mut driver = Driver::builder().target(DeviceType::PERKEY_RGB).save_restore(true).build().unwrap();
+    let mut routine = Routine::new();
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    while routine.step() != Step::Done {
+        driver.set_lighting_from_bitmap(&routine.pattern()).unwrap();
+        println!("{}", routine.instructions());
+
+        let line = match lines.next() {
+            Some(line) => line.unwrap(),
+            None => return,
+        };
+        let step = 0.01 * line.len().max(1) as f32;
+        match line.chars().next() {
+            Some('+') => routine.adjust(step),
+            Some('-') => routine.adjust(-step),
+            _ => routine.confirm(),
+        }
+    }
+
+    routine.calibration().save("keyboard.cal").unwrap();
+    print!("{}", routine.calibration());
+}
@@ -11,7 +11,7 @@
 #[macro_use]
 extern crate bitflags;
 
-use std::os::raw::{c_int, c_uint, c_double};
+use std::os::raw::{c_int, c_uint, c_double, c_char};
 
 pub const BITMAP_WIDTH: usize         = 21;
 pub const BITMAP_HEIGHT: usize        = 6;
@@ -152,6 +152,17 @@ pub enum Key {
     G_BADGE            = 0xFFFF2
 }
 
+/// Device type addressed by `LogiLedSetLightingForTargetZone`.
+#[repr(C)]
+#[derive(Clone, Copy, Hash, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ZoneTarget {
+    Keyboard = 0x0,
+    Mouse    = 0x3,
+    Mousemat = 0x4,
+    Headset  = 0x8,
+    Speaker  = 0xe,
+}
+
 #[derive(Debug)]
 pub struct Library {
     pub LogiLedInit: unsafe extern "C" fn() -> bool,
@@ -198,6 +209,16 @@ pub struct Library {
 
     pub LogiLedShutdown: unsafe extern "C" fn(),
 
+    // Optional functions => missing in older versions of the SDK.
+    pub LogiLedInitWithName: Option<unsafe extern "C" fn(name: *const c_char) -> bool>,
+    pub LogiLedGetSdkVersion: Option<unsafe extern "C" fn(majorNum: *mut c_int, minorNum: *mut c_int,
+        buildNum: *mut c_int) -> bool>,
+    pub LogiLedSetLightingForTargetZone: Option<unsafe extern "C" fn(deviceType: ZoneTarget, zone: c_int,
+        redPercentage: c_int, greenPercentage: c_int, bluePercentage: c_int) -> bool>,
+    /// Bitmap with one BGRA entry per zone of `deviceType`, `bitmapSize` is in bytes.
+    pub LogiLedSetLightingFromBitmapForTargetDevice: Option<unsafe extern "C" fn(deviceType: ZoneTarget,
+        bitmap: *const u8, bitmapSize: c_int) -> bool>,
+
     /// Library handle, will be freed on drop
     _handle: platform::Handle,
 }
@@ -206,16 +227,20 @@ pub struct Library {
 mod platform {
     use super::Library;
     use std::io::{Error, ErrorKind};
+    use std::path::Path;
 
-    pub struct Handle(*const ());
+    #[derive(Debug)]
+    pub struct Handle;
 
     impl Library {
         pub fn load() -> Result<Library, Error> {
             Err(Error::new(ErrorKind::Other, "Unsupported system"))
         }
-    }
 
-    unsafe impl Send for Handle {}
+        pub fn load_from<P: AsRef<Path>>(_path: P) -> Result<Library, Error> {
+            Err(Error::new(ErrorKind::Other, "Unsupported system"))
+        }
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -233,6 +258,7 @@ mod platform {
     use std::os::windows::ffi::OsStrExt;
     use std::ffi::OsStr;
     use std::io::Error;
+    use std::path::Path;
     use std::fmt;
 
     pub struct Handle(HMODULE);
@@ -330,77 +356,109 @@ mod platform {
     impl Library {
         /// Try to locate and load 'LogitechLed.dll'.
         pub fn load() -> Result<Library, Error> {
-            use std::mem;
+            unsafe {
+                Library::from_handle(load_lib()?)
+            }
+        }
 
+        /// Load the library at `path`, skipping the registry lookup.
+        pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Library, Error> {
+            // Convert to widestring and terminate with \0\0.
+            let wide_path = path.as_ref().as_os_str().encode_wide().chain(Some(0)).collect::<Vec<u16>>();
             unsafe {
-                let handle = load_lib()?;
-
-                let mut symbols = [
-                    ("LogiLedInit\0",                            0 as FARPROC),
-                    ("LogiGetConfigOptionNumber\0",              0 as FARPROC),
-                    ("LogiGetConfigOptionBool\0",                0 as FARPROC),
-                    ("LogiGetConfigOptionColor\0",               0 as FARPROC),
-                    ("LogiGetConfigOptionKeyInput\0",            0 as FARPROC),
-                    ("LogiSetConfigOptionLabel\0",               0 as FARPROC),
-                    ("LogiLedSetTargetDevice\0",                 0 as FARPROC),
-                    ("LogiLedSaveCurrentLighting\0",             0 as FARPROC),
-                    ("LogiLedSetLighting\0",                     0 as FARPROC),
-                    ("LogiLedRestoreLighting\0",                 0 as FARPROC),
-                    ("LogiLedFlashLighting\0",                   0 as FARPROC),
-                    ("LogiLedPulseLighting\0",                   0 as FARPROC),
-                    ("LogiLedStopEffects\0",                     0 as FARPROC),
-                    ("LogiLedSetLightingFromBitmap\0",           0 as FARPROC),
-                    ("LogiLedSetLightingForKeyWithScanCode\0",   0 as FARPROC),
-                    ("LogiLedSetLightingForKeyWithHidCode\0",    0 as FARPROC),
-                    ("LogiLedSetLightingForKeyWithQuartzCode\0", 0 as FARPROC),
-                    ("LogiLedSetLightingForKeyWithKeyName\0",    0 as FARPROC),
-                    ("LogiLedSaveLightingForKey\0",              0 as FARPROC),
-                    ("LogiLedRestoreLightingForKey\0",           0 as FARPROC),
-                    ("LogiLedExcludeKeysFromBitmap\0",           0 as FARPROC),
-                    ("LogiLedFlashSingleKey\0",                  0 as FARPROC),
-                    ("LogiLedPulseSingleKey\0",                  0 as FARPROC),
-                    ("LogiLedStopEffectsOnKey\0",                0 as FARPROC),
-                    ("LogiLedShutdown\0",                        0 as FARPROC),
-                ];
-
-                for i in symbols.iter_mut() {
-                    i.1 = kernel32::GetProcAddress(handle, i.0.as_ptr() as *const i8);
-                    if i.1.is_null() {
-                        let error = Error::last_os_error();
-                        kernel32::FreeLibrary(handle);
-                        return Err(error);
-                    }
+                let handle = kernel32::LoadLibraryW(wide_path.as_ptr());
+                if handle.is_null() {
+                    return Err(Error::last_os_error());
+                }
+                Library::from_handle(handle)
+            }
+        }
+
+        /// Resolve the SDK symbols, frees `handle` on failure.
+        unsafe fn from_handle(handle: HMODULE) -> Result<Library, Error> {
+            use std::mem;
+
+            let mut symbols = [
+                ("LogiLedInit\0",                            0 as FARPROC),
+                ("LogiGetConfigOptionNumber\0",              0 as FARPROC),
+                ("LogiGetConfigOptionBool\0",                0 as FARPROC),
+                ("LogiGetConfigOptionColor\0",               0 as FARPROC),
+                ("LogiGetConfigOptionKeyInput\0",            0 as FARPROC),
+                ("LogiSetConfigOptionLabel\0",               0 as FARPROC),
+                ("LogiLedSetTargetDevice\0",                 0 as FARPROC),
+                ("LogiLedSaveCurrentLighting\0",             0 as FARPROC),
+                ("LogiLedSetLighting\0",                     0 as FARPROC),
+                ("LogiLedRestoreLighting\0",                 0 as FARPROC),
+                ("LogiLedFlashLighting\0",                   0 as FARPROC),
+                ("LogiLedPulseLighting\0",                   0 as FARPROC),
+                ("LogiLedStopEffects\0",                     0 as FARPROC),
+                ("LogiLedSetLightingFromBitmap\0",           0 as FARPROC),
+                ("LogiLedSetLightingForKeyWithScanCode\0",   0 as FARPROC),
+                ("LogiLedSetLightingForKeyWithHidCode\0",    0 as FARPROC),
+                ("LogiLedSetLightingForKeyWithQuartzCode\0", 0 as FARPROC),
+                ("LogiLedSetLightingForKeyWithKeyName\0",    0 as FARPROC),
+                ("LogiLedSaveLightingForKey\0",              0 as FARPROC),
+                ("LogiLedRestoreLightingForKey\0",           0 as FARPROC),
+                ("LogiLedExcludeKeysFromBitmap\0",           0 as FARPROC),
+                ("LogiLedFlashSingleKey\0",                  0 as FARPROC),
+                ("LogiLedPulseSingleKey\0",                  0 as FARPROC),
+                ("LogiLedStopEffectsOnKey\0",                0 as FARPROC),
+                ("LogiLedShutdown\0",                        0 as FARPROC),
+            ];
+
+            for i in symbols.iter_mut() {
+                i.1 = kernel32::GetProcAddress(handle, i.0.as_ptr() as *const i8);
+                if i.1.is_null() {
+                    let error = Error::last_os_error();
+                    kernel32::FreeLibrary(handle);
+                    return Err(error);
                 }
+            }
+
+            // NULL if missing, which transmutes to None.
+            let mut optional = [
+                ("LogiLedInitWithName\0",                    0 as FARPROC),
+                ("LogiLedGetSdkVersion\0",                   0 as FARPROC),
+                ("LogiLedSetLightingForTargetZone\0",        0 as FARPROC),
+                ("LogiLedSetLightingFromBitmapForTargetDevice\0", 0 as FARPROC),
+            ];
 
-                Ok(Library {
-                    LogiLedInit:                            mem::transmute(symbols[0].1),
-                    LogiGetConfigOptionNumber:              mem::transmute(symbols[1].1),
-                    LogiGetConfigOptionBool:                mem::transmute(symbols[2].1),
-                    LogiGetConfigOptionColor:               mem::transmute(symbols[3].1),
-                    LogiGetConfigOptionKeyInput:            mem::transmute(symbols[4].1),
-                    LogiSetConfigOptionLabel:               mem::transmute(symbols[5].1),
-                    LogiLedSetTargetDevice:                 mem::transmute(symbols[6].1),
-                    LogiLedSaveCurrentLighting:             mem::transmute(symbols[7].1),
-                    LogiLedSetLighting:                     mem::transmute(symbols[8].1),
-                    LogiLedRestoreLighting:                 mem::transmute(symbols[9].1),
-                    LogiLedFlashLighting:                   mem::transmute(symbols[10].1),
-                    LogiLedPulseLighting:                   mem::transmute(symbols[11].1),
-                    LogiLedStopEffects:                     mem::transmute(symbols[12].1),
-                    LogiLedSetLightingFromBitmap:           mem::transmute(symbols[13].1),
-                    LogiLedSetLightingForKeyWithScanCode:   mem::transmute(symbols[14].1),
-                    LogiLedSetLightingForKeyWithHidCode:    mem::transmute(symbols[15].1),
-                    LogiLedSetLightingForKeyWithQuartzCode: mem::transmute(symbols[16].1),
-                    LogiLedSetLightingForKeyWithKeyName:    mem::transmute(symbols[17].1),
-                    LogiLedSaveLightingForKey:              mem::transmute(symbols[18].1),
-                    LogiLedRestoreLightingForKey:           mem::transmute(symbols[19].1),
-                    LogiLedExcludeKeysFromBitmap:           mem::transmute(symbols[20].1),
-                    LogiLedFlashSingleKey:                  mem::transmute(symbols[21].1),
-                    LogiLedPulseSingleKey:                  mem::transmute(symbols[22].1),
-                    LogiLedStopEffectsOnKey:                mem::transmute(symbols[23].1),
-                    LogiLedShutdown:                        mem::transmute(symbols[24].1),
-                    _handle: Handle(handle),
-                })
+            for i in optional.iter_mut() {
+                i.1 = kernel32::GetProcAddress(handle, i.0.as_ptr() as *const i8);
             }
+
+            Ok(Library {
+                LogiLedInit:                            mem::transmute(symbols[0].1),
+                LogiGetConfigOptionNumber:              mem::transmute(symbols[1].1),
+                LogiGetConfigOptionBool:                mem::transmute(symbols[2].1),
+                LogiGetConfigOptionColor:               mem::transmute(symbols[3].1),
+                LogiGetConfigOptionKeyInput:            mem::transmute(symbols[4].1),
+                LogiSetConfigOptionLabel:               mem::transmute(symbols[5].1),
+                LogiLedSetTargetDevice:                 mem::transmute(symbols[6].1),
+                LogiLedSaveCurrentLighting:             mem::transmute(symbols[7].1),
+                LogiLedSetLighting:                     mem::transmute(symbols[8].1),
+                LogiLedRestoreLighting:                 mem::transmute(symbols[9].1),
+                LogiLedFlashLighting:                   mem::transmute(symbols[10].1),
+                LogiLedPulseLighting:                   mem::transmute(symbols[11].1),
+                LogiLedStopEffects:                     mem::transmute(symbols[12].1),
+                LogiLedSetLightingFromBitmap:           mem::transmute(symbols[13].1),
+                LogiLedSetLightingForKeyWithScanCode:   mem::transmute(symbols[14].1),
+                LogiLedSetLightingForKeyWithHidCode:    mem::transmute(symbols[15].1),
+                LogiLedSetLightingForKeyWithQuartzCode: mem::transmute(symbols[16].1),
+                LogiLedSetLightingForKeyWithKeyName:    mem::transmute(symbols[17].1),
+                LogiLedSaveLightingForKey:              mem::transmute(symbols[18].1),
+                LogiLedRestoreLightingForKey:           mem::transmute(symbols[19].1),
+                LogiLedExcludeKeysFromBitmap:           mem::transmute(symbols[20].1),
+                LogiLedFlashSingleKey:                  mem::transmute(symbols[21].1),
+                LogiLedPulseSingleKey:                  mem::transmute(symbols[22].1),
+                LogiLedStopEffectsOnKey:                mem::transmute(symbols[23].1),
+                LogiLedShutdown:                        mem::transmute(symbols[24].1),
+                LogiLedInitWithName:                    mem::transmute(optional[0].1),
+                LogiLedGetSdkVersion:                   mem::transmute(optional[1].1),
+                LogiLedSetLightingForTargetZone:        mem::transmute(optional[2].1),
+                LogiLedSetLightingFromBitmapForTargetDevice: mem::transmute(optional[3].1),
+                _handle: Handle(handle),
+            })
         }
     }
 
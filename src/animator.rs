@@ -0,0 +1,206 @@
+//! Playing effects in real time.
+//!
+//! An [`Animator`](struct.Animator.html) renders an
+//! [`Effect`](../effect/trait.Effect.html) on a fixed grid of frame times and
+//! sends each frame with
+//! [`Driver::update_lighting_from_frame`](../struct.Driver.html#method.update_lighting_from_frame),
+//! so only the keys that changed are sent. When sending falls behind, the
+//! frames that are already late are skipped instead of played back too slow.
+//!
+//! [`run`](struct.Animator.html#method.run) drives the animator from the
+//! system clock. [`advance`](struct.Animator.html#method.advance) takes the
+//! time from the caller instead, for event loops and deterministic tests.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::{Driver, Color, FloatFrame};
+//! use led::animator::{Animator, Tick};
+//! use led::effect;
+//! use led::mock::MockBackend;
+//! use std::time::Duration;
+//!
+//! # fn main() {
+//! let mut driver = Driver::with_backend(MockBackend::new()).unwrap();
+//! let blink = effect::from_fn(|t: Duration, frame: &mut FloatFrame| {
+//!     if t.as_millis() % 200 < 100 { frame.fill(Color::WHITE) }
+//! }).with_duration(Duration::from_secs(1));
+//!
+//! let mut animator = Animator::new(blink, 10);
+//! assert_eq!(animator.advance(&mut driver, Duration::from_millis(0)).unwrap(), Tick::Frame(Duration::from_millis(0)));
+//! assert_eq!(animator.advance(&mut driver, Duration::from_millis(40)).unwrap(), Tick::Wait(Duration::from_millis(60)));
+//!
+//! // Late by two frames, they are skipped.
+//! assert_eq!(animator.advance(&mut driver, Duration::from_millis(350)).unwrap(), Tick::Frame(Duration::from_millis(300)));
+//! assert_eq!(animator.skipped(), 2);
+//! # }
+//! ```
+
+use effect::Effect;
+use frame::FloatFrame;
+use color::Color;
+use {Driver, Error};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What [`Animator::advance`](struct.Animator.html#method.advance) did.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tick {
+    /// Rendered and sent the frame for this time.
+    Frame(Duration),
+    /// The next frame is due after this long.
+    Wait(Duration),
+    /// The effect has ended, its last frame was sent.
+    Finished,
+}
+
+/// Plays an effect at a target frame rate, see the [module docs](index.html).
+#[derive(Debug)]
+pub struct Animator<E> {
+    effect: E,
+    interval: Duration,
+    frame: FloatFrame,
+    /// Index of the next frame on the grid.
+    next: u64,
+    /// Time of the last frame sent.
+    last: Option<Duration>,
+    frames: u64,
+    skipped: u64,
+}
+
+/// Time of frame `n` on a grid of `interval`.
+fn frame_time(interval: Duration, n: u64) -> Duration {
+    let nanos = interval.as_nanos() * n as u128;
+    Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+}
+
+impl<E: Effect> Animator<E> {
+    /// Play `effect` at `fps` frames per second.
+    ///
+    /// # Panics
+    /// If `fps` is 0.
+    pub fn new(effect: E, fps: u32) -> Animator<E> {
+        assert!(fps > 0, "frame rate must be above 0");
+        Animator {
+            effect,
+            interval: Duration::from_secs(1) / fps,
+            frame: FloatFrame::new(),
+            next: 0,
+            last: None,
+            frames: 0,
+            skipped: 0,
+        }
+    }
+
+    pub fn effect(&self) -> &E {
+        &self.effect
+    }
+
+    pub fn effect_mut(&mut self) -> &mut E {
+        &mut self.effect
+    }
+
+    /// Time between frames at the target frame rate.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Time between frames on `driver`, never shorter than its
+    /// [pacing](../struct.Pacing.html#method.min_interval) allows for one call.
+    pub fn interval_for(&self, driver: &Driver) -> Duration {
+        self.interval.max(driver.pacing().min_interval())
+    }
+
+    /// The last frame rendered.
+    pub fn frame(&self) -> &FloatFrame {
+        &self.frame
+    }
+
+    /// Number of frames sent.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Number of frames skipped because they were already late.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Returns `true` once the last frame of the effect was sent.
+    pub fn is_finished(&self) -> bool {
+        match (self.effect.duration(), self.last) {
+            (Some(d), Some(last)) => last >= d,
+            _ => false,
+        }
+    }
+
+    /// Start over from time 0.
+    pub fn reset(&mut self) {
+        self.next = 0;
+        self.last = None;
+        self.frames = 0;
+        self.skipped = 0;
+    }
+
+    /// Render the effect at `t` and send it, regardless of the schedule.
+    pub fn tick(&mut self, driver: &mut Driver, t: Duration) -> Result<(), Error> {
+        self.frame.fill(Color::BLACK);
+        self.effect.render(t, &mut self.frame);
+        driver.update_lighting_from_frame(&self.frame)?;
+        self.frames += 1;
+        self.last = Some(t);
+        Ok(())
+    }
+
+    /// Send the frame due at `now` since the start, if any.
+    ///
+    /// Frames that are due but older than the latest one are skipped. The
+    /// last frame of an effect with a duration is rendered at its end.
+    pub fn advance(&mut self, driver: &mut Driver, now: Duration) -> Result<Tick, Error> {
+        if self.is_finished() {
+            return Ok(Tick::Finished);
+        }
+
+        let interval = self.interval_for(driver);
+        let due = frame_time(interval, self.next);
+        if now < due {
+            return Ok(Tick::Wait(due - now));
+        }
+
+        let current = (now.as_nanos() / interval.as_nanos().max(1)) as u64;
+        let t = match self.effect.duration() {
+            Some(d) => frame_time(interval, current).min(d),
+            None => frame_time(interval, current),
+        };
+        self.tick(driver, t)?;
+        self.skipped += current - self.next;
+        self.next = current + 1;
+        Ok(Tick::Frame(t))
+    }
+
+    /// Play the effect until it ends, forever if it has no duration.
+    pub fn run(&mut self, driver: &mut Driver) -> Result<(), Error> {
+        self.play(driver, None)
+    }
+
+    /// Play the effect for `duration`, or until it ends.
+    pub fn run_for(&mut self, driver: &mut Driver, duration: Duration) -> Result<(), Error> {
+        self.play(driver, Some(duration))
+    }
+
+    fn play(&mut self, driver: &mut Driver, limit: Option<Duration>) -> Result<(), Error> {
+        // Carry on from the next frame when resumed.
+        let offset = frame_time(self.interval_for(driver), self.next);
+        let start = Instant::now();
+        loop {
+            let elapsed = start.elapsed();
+            if limit.is_some_and(|l| elapsed >= l) {
+                return Ok(());
+            }
+            match self.advance(driver, offset + elapsed)? {
+                Tick::Frame(_) => {},
+                Tick::Wait(d) => thread::sleep(limit.map_or(d, |l| d.min(l - elapsed))),
+                Tick::Finished => return Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Backend abstraction over the SDK entry points.
+//!
+//! [`Driver`](../struct.Driver.html) talks to the SDK through the
+//! [`Backend`](trait.Backend.html) trait. It is implemented for the dynamically
+//! loaded [`Library`](../../logitech_led_sys/struct.Library.html) and for the
+//! [`MockBackend`](../mock/struct.MockBackend.html) used in tests.
+
+use sys::{self, Library, Key, DeviceType, ZoneTarget};
+use std::os::raw::{c_int, c_char};
+use std::ffi::CString;
+use std::fmt;
+
+/// Color in the percent representation used by the SDK.
+pub type Percent3 = (c_int, c_int, c_int);
+
+/// SDK function, used to select which calls are retried and to identify failed calls.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Operation {
+    Init,
+    InitWithName,
+    GetSdkVersion,
+    GetConfigOptionNumber,
+    GetConfigOptionBool,
+    GetConfigOptionColor,
+    GetConfigOptionKeyInput,
+    SetConfigOptionLabel,
+    SetTargetDevice,
+    SaveCurrentLighting,
+    SetLighting,
+    RestoreLighting,
+    FlashLighting,
+    PulseLighting,
+    StopEffects,
+    SetLightingFromBitmap,
+    SetLightingForKeyWithScanCode,
+    SetLightingForKeyWithHidCode,
+    SetLightingForKeyWithQuartzCode,
+    SetLightingForKeyWithKeyName,
+    SaveLightingForKey,
+    RestoreLightingForKey,
+    ExcludeKeysFromBitmap,
+    FlashSingleKey,
+    PulseSingleKey,
+    StopEffectsOnKey,
+    SetLightingForTargetZone,
+    SetLightingFromBitmapForTargetDevice,
+    Shutdown,
+}
+
+impl Operation {
+    /// Every operation, in declaration order.
+    pub const ALL: [Operation; 29] = [
+        Operation::Init,
+        Operation::InitWithName,
+        Operation::GetSdkVersion,
+        Operation::GetConfigOptionNumber,
+        Operation::GetConfigOptionBool,
+        Operation::GetConfigOptionColor,
+        Operation::GetConfigOptionKeyInput,
+        Operation::SetConfigOptionLabel,
+        Operation::SetTargetDevice,
+        Operation::SaveCurrentLighting,
+        Operation::SetLighting,
+        Operation::RestoreLighting,
+        Operation::FlashLighting,
+        Operation::PulseLighting,
+        Operation::StopEffects,
+        Operation::SetLightingFromBitmap,
+        Operation::SetLightingForKeyWithScanCode,
+        Operation::SetLightingForKeyWithHidCode,
+        Operation::SetLightingForKeyWithQuartzCode,
+        Operation::SetLightingForKeyWithKeyName,
+        Operation::SaveLightingForKey,
+        Operation::RestoreLightingForKey,
+        Operation::ExcludeKeysFromBitmap,
+        Operation::FlashSingleKey,
+        Operation::PulseSingleKey,
+        Operation::StopEffectsOnKey,
+        Operation::SetLightingForTargetZone,
+        Operation::SetLightingFromBitmapForTargetDevice,
+        Operation::Shutdown,
+    ];
+
+    /// Name of the exported SDK function.
+    pub fn sdk_function(self) -> &'static str {
+        match self {
+            Operation::Init => "LogiLedInit",
+            Operation::InitWithName => "LogiLedInitWithName",
+            Operation::GetSdkVersion => "LogiLedGetSdkVersion",
+            Operation::GetConfigOptionNumber => "LogiGetConfigOptionNumber",
+            Operation::GetConfigOptionBool => "LogiGetConfigOptionBool",
+            Operation::GetConfigOptionColor => "LogiGetConfigOptionColor",
+            Operation::GetConfigOptionKeyInput => "LogiGetConfigOptionKeyInput",
+            Operation::SetConfigOptionLabel => "LogiSetConfigOptionLabel",
+            Operation::SetTargetDevice => "LogiLedSetTargetDevice",
+            Operation::SaveCurrentLighting => "LogiLedSaveCurrentLighting",
+            Operation::SetLighting => "LogiLedSetLighting",
+            Operation::RestoreLighting => "LogiLedRestoreLighting",
+            Operation::FlashLighting => "LogiLedFlashLighting",
+            Operation::PulseLighting => "LogiLedPulseLighting",
+            Operation::StopEffects => "LogiLedStopEffects",
+            Operation::SetLightingFromBitmap => "LogiLedSetLightingFromBitmap",
+            Operation::SetLightingForKeyWithScanCode => "LogiLedSetLightingForKeyWithScanCode",
+            Operation::SetLightingForKeyWithHidCode => "LogiLedSetLightingForKeyWithHidCode",
+            Operation::SetLightingForKeyWithQuartzCode => "LogiLedSetLightingForKeyWithQuartzCode",
+            Operation::SetLightingForKeyWithKeyName => "LogiLedSetLightingForKeyWithKeyName",
+            Operation::SaveLightingForKey => "LogiLedSaveLightingForKey",
+            Operation::RestoreLightingForKey => "LogiLedRestoreLightingForKey",
+            Operation::ExcludeKeysFromBitmap => "LogiLedExcludeKeysFromBitmap",
+            Operation::FlashSingleKey => "LogiLedFlashSingleKey",
+            Operation::PulseSingleKey => "LogiLedPulseSingleKey",
+            Operation::StopEffectsOnKey => "LogiLedStopEffectsOnKey",
+            Operation::SetLightingForTargetZone => "LogiLedSetLightingForTargetZone",
+            Operation::SetLightingFromBitmapForTargetDevice => "LogiLedSetLightingFromBitmapForTargetDevice",
+            Operation::Shutdown => "LogiLedShutdown",
+        }
+    }
+
+    /// Returns `true` for functions that are missing in older versions of the SDK.
+    pub fn is_optional(self) -> bool {
+        matches!(self,
+            Operation::InitWithName |
+            Operation::GetSdkVersion |
+            Operation::SetLightingForTargetZone |
+            Operation::SetLightingFromBitmapForTargetDevice)
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.sdk_function())
+    }
+}
+
+/// Safe interface to the SDK entry points.
+///
+/// Every method maps directly to one SDK function and returns its result.
+/// Strings are NUL terminated UTF-16 and durations are in milliseconds,
+/// as the SDK expects them.
+pub trait Backend: fmt::Debug + Send {
+    /// Returns `true` if the backend controls process global state,
+    /// so only one [`Driver`](../struct.Driver.html) may use it at a time.
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the backend implements `op`.
+    ///
+    /// Only [optional](enum.Operation.html#method.is_optional) operations may be
+    /// unsupported, and the default implementation supports none of them.
+    fn supports(&self, op: Operation) -> bool {
+        !op.is_optional()
+    }
+
+    fn init(&mut self) -> bool;
+
+    /// Initialize and register the application under `name`.
+    ///
+    /// Backends without `LogiLedInitWithName` fall back to `LogiLedInit`.
+    fn init_with_name(&mut self, _name: &str) -> bool {
+        self.init()
+    }
+
+    fn get_config_option_number(&mut self, path: &[u16], value: &mut f64) -> bool;
+    fn get_config_option_bool(&mut self, path: &[u16], value: &mut bool) -> bool;
+    fn get_config_option_color(&mut self, path: &[u16], value: &mut Percent3) -> bool;
+    fn set_config_option_label(&mut self, path: &[u16], label: &mut [u16]) -> bool;
+    fn set_target_device(&mut self, device_type: DeviceType) -> bool;
+    fn save_current_lighting(&mut self) -> bool;
+    fn set_lighting(&mut self, color: Percent3) -> bool;
+    fn restore_lighting(&mut self) -> bool;
+    fn flash_lighting(&mut self, color: Percent3, duration: c_int, interval: c_int) -> bool;
+    fn pulse_lighting(&mut self, color: Percent3, duration: c_int, interval: c_int) -> bool;
+    fn stop_effects(&mut self) -> bool;
+    fn set_lighting_from_bitmap(&mut self, bitmap: &[u8]) -> bool;
+    fn set_lighting_for_key(&mut self, key: Key, color: Percent3) -> bool;
+    fn save_lighting_for_key(&mut self, key: Key) -> bool;
+    fn restore_lighting_for_key(&mut self, key: Key) -> bool;
+    fn exclude_keys_from_bitmap(&mut self, keys: &[Key]) -> bool;
+    fn flash_single_key(&mut self, key: Key, color: Percent3, duration: c_int, interval: c_int) -> bool;
+    fn pulse_single_key(&mut self, key: Key, start: Percent3, finish: Percent3,
+        duration: c_int, infinite: bool) -> bool;
+    fn stop_effects_on_key(&mut self, key: Key) -> bool;
+
+    /// Returns `false` if unsupported.
+    fn get_sdk_version(&mut self, _version: &mut (c_int, c_int, c_int)) -> bool {
+        false
+    }
+
+    /// Returns `false` if unsupported.
+    fn set_lighting_for_target_zone(&mut self, _target: ZoneTarget, _zone: c_int, _color: Percent3) -> bool {
+        false
+    }
+
+    /// Returns `false` if unsupported.
+    fn set_lighting_from_bitmap_for_target_device(&mut self, _target: ZoneTarget, _bitmap: &[u8]) -> bool {
+        false
+    }
+
+    fn shutdown(&mut self);
+}
+
+impl Backend for Library {
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn supports(&self, op: Operation) -> bool {
+        match op {
+            Operation::InitWithName => self.LogiLedInitWithName.is_some(),
+            Operation::GetSdkVersion => self.LogiLedGetSdkVersion.is_some(),
+            Operation::SetLightingForTargetZone => self.LogiLedSetLightingForTargetZone.is_some(),
+            Operation::SetLightingFromBitmapForTargetDevice =>
+                self.LogiLedSetLightingFromBitmapForTargetDevice.is_some(),
+            _ => true,
+        }
+    }
+
+    fn init(&mut self) -> bool {
+        unsafe { (self.LogiLedInit)() }
+    }
+
+    fn init_with_name(&mut self, name: &str) -> bool {
+        let init_with_name = match self.LogiLedInitWithName {
+            Some(f) => f,
+            None => return self.init(),
+        };
+        match CString::new(name) {
+            Ok(name) => unsafe { init_with_name(name.as_ptr() as *const c_char) },
+            Err(_) => false,
+        }
+    }
+
+    fn get_config_option_number(&mut self, path: &[u16], value: &mut f64) -> bool {
+        unsafe { (self.LogiGetConfigOptionNumber)(path.as_ptr(), value as *mut _) }
+    }
+
+    fn get_config_option_bool(&mut self, path: &[u16], value: &mut bool) -> bool {
+        unsafe { (self.LogiGetConfigOptionBool)(path.as_ptr(), value as *mut _) }
+    }
+
+    fn get_config_option_color(&mut self, path: &[u16], value: &mut Percent3) -> bool {
+        unsafe {
+            (self.LogiGetConfigOptionColor)(path.as_ptr(),
+                (&mut value.0) as *mut _, (&mut value.1) as *mut _, (&mut value.2) as *mut _)
+        }
+    }
+
+    fn set_config_option_label(&mut self, path: &[u16], label: &mut [u16]) -> bool {
+        unsafe { (self.LogiSetConfigOptionLabel)(path.as_ptr(), label.as_mut_ptr()) }
+    }
+
+    fn set_target_device(&mut self, device_type: DeviceType) -> bool {
+        unsafe { (self.LogiLedSetTargetDevice)(device_type.bits()) }
+    }
+
+    fn save_current_lighting(&mut self) -> bool {
+        unsafe { (self.LogiLedSaveCurrentLighting)() }
+    }
+
+    fn set_lighting(&mut self, c: Percent3) -> bool {
+        unsafe { (self.LogiLedSetLighting)(c.0, c.1, c.2) }
+    }
+
+    fn restore_lighting(&mut self) -> bool {
+        unsafe { (self.LogiLedRestoreLighting)() }
+    }
+
+    fn flash_lighting(&mut self, c: Percent3, duration: c_int, interval: c_int) -> bool {
+        unsafe { (self.LogiLedFlashLighting)(c.0, c.1, c.2, duration, interval) }
+    }
+
+    fn pulse_lighting(&mut self, c: Percent3, duration: c_int, interval: c_int) -> bool {
+        unsafe { (self.LogiLedPulseLighting)(c.0, c.1, c.2, duration, interval) }
+    }
+
+    fn stop_effects(&mut self) -> bool {
+        unsafe { (self.LogiLedStopEffects)() }
+    }
+
+    fn set_lighting_from_bitmap(&mut self, bitmap: &[u8]) -> bool {
+        assert_eq!(bitmap.len(), sys::BITMAP_SIZE);
+        unsafe { (self.LogiLedSetLightingFromBitmap)(bitmap.as_ptr()) }
+    }
+
+    fn set_lighting_for_key(&mut self, key: Key, c: Percent3) -> bool {
+        unsafe { (self.LogiLedSetLightingForKeyWithKeyName)(key, c.0, c.1, c.2) }
+    }
+
+    fn save_lighting_for_key(&mut self, key: Key) -> bool {
+        unsafe { (self.LogiLedSaveLightingForKey)(key) }
+    }
+
+    fn restore_lighting_for_key(&mut self, key: Key) -> bool {
+        unsafe { (self.LogiLedRestoreLightingForKey)(key) }
+    }
+
+    fn exclude_keys_from_bitmap(&mut self, keys: &[Key]) -> bool {
+        unsafe { (self.LogiLedExcludeKeysFromBitmap)(keys.as_ptr(), keys.len() as c_int) }
+    }
+
+    fn flash_single_key(&mut self, key: Key, c: Percent3, duration: c_int, interval: c_int) -> bool {
+        unsafe { (self.LogiLedFlashSingleKey)(key, c.0, c.1, c.2, duration, interval) }
+    }
+
+    fn pulse_single_key(&mut self, key: Key, s: Percent3, f: Percent3,
+        duration: c_int, infinite: bool) -> bool
+    {
+        unsafe {
+            (self.LogiLedPulseSingleKey)(
+                key,
+                s.0, s.1, s.2,
+                f.0, f.1, f.2,
+                duration,
+                infinite as c_int,
+            )
+        }
+    }
+
+    fn stop_effects_on_key(&mut self, key: Key) -> bool {
+        unsafe { (self.LogiLedStopEffectsOnKey)(key) }
+    }
+
+    fn get_sdk_version(&mut self, v: &mut (c_int, c_int, c_int)) -> bool {
+        match self.LogiLedGetSdkVersion {
+            Some(f) => unsafe { f((&mut v.0) as *mut _, (&mut v.1) as *mut _, (&mut v.2) as *mut _) },
+            None => false,
+        }
+    }
+
+    fn set_lighting_for_target_zone(&mut self, target: ZoneTarget, zone: c_int, c: Percent3) -> bool {
+        match self.LogiLedSetLightingForTargetZone {
+            Some(f) => unsafe { f(target, zone, c.0, c.1, c.2) },
+            None => false,
+        }
+    }
+
+    fn set_lighting_from_bitmap_for_target_device(&mut self, target: ZoneTarget, bitmap: &[u8]) -> bool {
+        match self.LogiLedSetLightingFromBitmapForTargetDevice {
+            Some(f) => unsafe { f(target, bitmap.as_ptr(), bitmap.len() as c_int) },
+            None => false,
+        }
+    }
+
+    fn shutdown(&mut self) {
+        unsafe { (self.LogiLedShutdown)() }
+    }
+}
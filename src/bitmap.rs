@@ -0,0 +1,217 @@
+//! Owned bitmap in the SDK layout, with key lookup and gradient painting.
+
+use color::{Color, ColorA, BGRA, BlendMode, Gradient};
+use keys::bitmap_index;
+use sys::{Key, BITMAP_WIDTH, BITMAP_HEIGHT, BITMAP_BYTES_PER_KEY, BITMAP_SIZE};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+
+/// How a gradient is laid out across a [`Bitmap`](struct.Bitmap.html).
+///
+/// Positions are in key cells, so one unit is the same distance both ways.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    /// Left to right.
+    Horizontal,
+    /// Top to bottom.
+    Vertical,
+    /// Outwards from the center `(x, y)` in cells, reaching the end at the farthest corner.
+    Radial { x: f32, y: f32 },
+    /// Along an angle in degrees, clockwise from left to right.
+    Angle(f32),
+}
+
+/// A [`BITMAP_SIZE`](constant.BITMAP_SIZE.html) byte BGRA bitmap,
+/// dereferences to the bytes for [`Driver::set_lighting_from_bitmap`](struct.Driver.html#method.set_lighting_from_bitmap).
+#[derive(Copy, Clone)]
+pub struct Bitmap([u8; BITMAP_SIZE]);
+
+impl Bitmap {
+    /// Black bitmap.
+    pub fn new() -> Bitmap {
+        Bitmap::filled(Color::BLACK)
+    }
+
+    /// Bitmap with every cell set to `color`.
+    pub fn filled(color: Color) -> Bitmap {
+        let mut b = Bitmap([0; BITMAP_SIZE]);
+        b.fill(color);
+        b
+    }
+
+    /// Fully transparent bitmap, a blank overlay for [`overlay`](#method.overlay).
+    pub fn transparent() -> Bitmap {
+        Bitmap([0; BITMAP_SIZE])
+    }
+
+    /// Copy of `bytes`.
+    ///
+    /// # Panics
+    /// If `bytes` is not [`BITMAP_SIZE`](constant.BITMAP_SIZE.html) bytes long.
+    pub fn from_bytes(bytes: &[u8]) -> Bitmap {
+        let mut b = Bitmap([0; BITMAP_SIZE]);
+        b.0.copy_from_slice(bytes);
+        b
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        let bgra = BGRA::from(color);
+        for cell in self.0.chunks_mut(BITMAP_BYTES_PER_KEY) {
+            cell.copy_from_slice(&bgra);
+        }
+    }
+
+    fn offset(x: usize, y: usize) -> usize {
+        assert!(x < BITMAP_WIDTH && y < BITMAP_HEIGHT, "bitmap position ({}, {}) out of bounds", x, y);
+        (y * BITMAP_WIDTH + x) * BITMAP_BYTES_PER_KEY
+    }
+
+    /// Color of the cell at column `x` and row `y`, premultiplied by its alpha.
+    ///
+    /// # Panics
+    /// If the position is outside the bitmap.
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        let o = Bitmap::offset(x, y);
+        Color::from([self.0[o], self.0[o + 1], self.0[o + 2], self.0[o + 3]])
+    }
+
+    /// Set the cell at column `x` and row `y`.
+    ///
+    /// # Panics
+    /// If the position is outside the bitmap.
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        let o = Bitmap::offset(x, y);
+        self.0[o..o + BITMAP_BYTES_PER_KEY].copy_from_slice(&BGRA::from(color));
+    }
+
+    /// Color and alpha of the cell at column `x` and row `y`.
+    ///
+    /// # Panics
+    /// If the position is outside the bitmap.
+    pub fn get_rgba(&self, x: usize, y: usize) -> ColorA {
+        let o = Bitmap::offset(x, y);
+        ColorA::from([self.0[o], self.0[o + 1], self.0[o + 2], self.0[o + 3]])
+    }
+
+    /// Set the color and alpha of the cell at column `x` and row `y`.
+    ///
+    /// # Panics
+    /// If the position is outside the bitmap.
+    pub fn set_rgba(&mut self, x: usize, y: usize, color: ColorA) {
+        let o = Bitmap::offset(x, y);
+        self.0[o..o + BITMAP_BYTES_PER_KEY].copy_from_slice(&BGRA::from(color));
+    }
+
+    /// Composite `top` onto this bitmap cell by cell, respecting the alpha of both.
+    ///
+    /// Transparent cells of `top` leave the cell unchanged, and an opaque
+    /// bitmap stays opaque.
+    pub fn overlay(&mut self, top: &Bitmap, mode: BlendMode) {
+        for y in 0..BITMAP_HEIGHT {
+            for x in 0..BITMAP_WIDTH {
+                let c = top.get_rgba(x, y);
+                if c.a > 0.0 {
+                    let base = self.get_rgba(x, y);
+                    self.set_rgba(x, y, c.over(base, mode));
+                }
+            }
+        }
+    }
+
+    /// Color of `key`, `None` for keys outside the bitmap.
+    pub fn key(&self, key: Key) -> Option<Color> {
+        bitmap_index(key).map(|i| self.get(i % BITMAP_WIDTH, i / BITMAP_WIDTH))
+    }
+
+    /// Set the color of `key`. Returns `false` for keys outside the bitmap.
+    pub fn set_key(&mut self, key: Key, color: Color) -> bool {
+        match bitmap_index(key) {
+            Some(i) => {
+                self.set(i % BITMAP_WIDTH, i / BITMAP_WIDTH, color);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Set every cell to `f(x, y)`.
+    pub fn paint<F: FnMut(usize, usize) -> Color>(&mut self, mut f: F) {
+        for y in 0..BITMAP_HEIGHT {
+            for x in 0..BITMAP_WIDTH {
+                self.set(x, y, f(x, y));
+            }
+        }
+    }
+
+    /// Paint `gradient` across the bitmap in `direction`.
+    pub fn paint_gradient(&mut self, gradient: &Gradient, direction: Direction) {
+        let position = gradient_position(direction);
+        self.paint(|x, y| gradient.sample(position(x, y)));
+    }
+}
+
+/// Maps a cell to its position along a gradient in [0,1].
+pub(crate) fn gradient_position(direction: Direction) -> Box<dyn Fn(usize, usize) -> f32> {
+    let (w, h) = ((BITMAP_WIDTH - 1) as f32, (BITMAP_HEIGHT - 1) as f32);
+    let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)];
+
+    match direction {
+        Direction::Horizontal => Box::new(move |x, _| x as f32 / w),
+        Direction::Vertical => Box::new(move |_, y| y as f32 / h),
+        Direction::Radial { x: cx, y: cy } => {
+            let dist = move |x: f32, y: f32| (x - cx).hypot(y - cy);
+            let max = corners.iter().fold(0.0f32, |m, &(x, y)| m.max(dist(x, y)));
+            Box::new(move |x, y| if max > 0.0 { dist(x as f32, y as f32) / max } else { 0.0 })
+        },
+        Direction::Angle(deg) => {
+            let (sin, cos) = deg.to_radians().sin_cos();
+            let project = move |x: f32, y: f32| x * cos + y * sin;
+            let min = corners.iter().fold(f32::MAX, |m, &(x, y)| m.min(project(x, y)));
+            let max = corners.iter().fold(f32::MIN, |m, &(x, y)| m.max(project(x, y)));
+            Box::new(move |x, y| (project(x as f32, y as f32) - min) / (max - min))
+        },
+    }
+}
+
+impl Default for Bitmap {
+    fn default() -> Bitmap {
+        Bitmap::new()
+    }
+}
+
+impl PartialEq for Bitmap {
+    fn eq(&self, other: &Bitmap) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for Bitmap {}
+
+impl fmt::Debug for Bitmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut l = f.debug_list();
+        for y in 0..BITMAP_HEIGHT {
+            let row: Vec<String> = (0..BITMAP_WIDTH).map(|x| self.get(x, y).to_hex()).collect();
+            l.entry(&row.join(" "));
+        }
+        l.finish()
+    }
+}
+
+impl Deref for Bitmap {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for Bitmap {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
@@ -0,0 +1,190 @@
+//! Builder for a [`Driver`](../struct.Driver.html) with init options.
+
+use backend::Backend;
+use pacing::Pacing;
+use retry::RetryPolicy;
+use recovery::RecoveryPolicy;
+use fallback::Reducer;
+use probe::{self, Probe};
+use color::WhiteBalance;
+use calibration::Calibration;
+use dither::DitherMode;
+use sys::{DeviceType, Library};
+use {Driver, Error};
+use std::path::PathBuf;
+
+/// Options used to initialize a [`Driver`](../struct.Driver.html).
+///
+/// Created with [`Driver::builder`](../struct.Driver.html#method.builder).
+///
+/// ```no_run
+/// # extern crate logitech_led as led;
+/// # use std::time::Duration;
+/// # fn main() {
+/// let driver = led::Driver::builder()
+///     .app_name("My Game")
+///     .target(led::DeviceType::PERKEY_RGB)
+///     .pacing(led::Pacing::new(Duration::from_millis(10)).settle(Duration::from_secs(1)))
+///     .save_restore(true)
+///     .build()
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct DriverBuilder {
+    backend: Option<Box<dyn Backend>>,
+    library_path: Option<PathBuf>,
+    app_name: Option<String>,
+    target: Option<DeviceType>,
+    pacing: Pacing,
+    retry: RetryPolicy,
+    recovery: RecoveryPolicy,
+    save_restore: bool,
+    fallback: Option<Reducer>,
+    white_balance: Option<WhiteBalance>,
+    calibration: Option<Calibration>,
+    dither: DitherMode,
+}
+
+impl DriverBuilder {
+    pub fn new() -> DriverBuilder {
+        DriverBuilder::default()
+    }
+
+    /// Use `backend` instead of loading `LogitechLed.dll`.
+    pub fn backend<B: Backend + 'static>(mut self, backend: B) -> DriverBuilder {
+        self.backend = Some(Box::new(backend));
+        self
+    }
+
+    /// Load the SDK library from `path` instead of looking it up in the registry.
+    pub fn library_path<P: Into<PathBuf>>(mut self, path: P) -> DriverBuilder {
+        self.library_path = Some(path.into());
+        self
+    }
+
+    /// Initialize with `LogiLedInitWithName`, so the application shows up
+    /// under this name. Falls back to `LogiLedInit` if the SDK doesn't support it.
+    pub fn app_name<S: Into<String>>(mut self, name: S) -> DriverBuilder {
+        self.app_name = Some(name.into());
+        self
+    }
+
+    /// Target device type to set after initialization.
+    pub fn target(mut self, device_type: DeviceType) -> DriverBuilder {
+        self.target = Some(device_type);
+        self
+    }
+
+    pub fn pacing(mut self, pacing: Pacing) -> DriverBuilder {
+        self.pacing = pacing;
+        self
+    }
+
+    /// Retry policy, also used for `LogiLedInit`.
+    pub fn retry(mut self, policy: RetryPolicy) -> DriverBuilder {
+        self.retry = policy;
+        self
+    }
+
+    pub fn recovery(mut self, policy: RecoveryPolicy) -> DriverBuilder {
+        self.recovery = policy;
+        self
+    }
+
+    /// Save the current lighting after initialization
+    /// and restore it when the driver is dropped.
+    pub fn save_restore(mut self, save_restore: bool) -> DriverBuilder {
+        self.save_restore = save_restore;
+        self
+    }
+
+    /// Also show bitmaps on RGB and monochrome devices,
+    /// see [`Driver::set_fallback`](../struct.Driver.html#method.set_fallback).
+    pub fn fallback(mut self, reducer: Reducer) -> DriverBuilder {
+        self.fallback = Some(reducer);
+        self
+    }
+
+    /// Correct the white of the devices,
+    /// see [`Driver::set_white_balance`](../struct.Driver.html#method.set_white_balance).
+    pub fn white_balance(mut self, white_balance: WhiteBalance) -> DriverBuilder {
+        self.white_balance = Some(white_balance);
+        self
+    }
+
+    /// Correct the response of the devices,
+    /// see [`Driver::set_calibration`](../struct.Driver.html#method.set_calibration).
+    pub fn calibration(mut self, calibration: Calibration) -> DriverBuilder {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    /// How colors are quantized to percent,
+    /// see [`Driver::set_dither`](../struct.Driver.html#method.set_dither).
+    pub fn dither(mut self, mode: DitherMode) -> DriverBuilder {
+        self.dither = mode;
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.backend.is_some() && self.library_path.is_some() {
+            return Err(Error::InvalidConfig("a backend and a library path can't both be set"));
+        }
+        if let Some(ref name) = self.app_name {
+            if name.is_empty() {
+                return Err(Error::InvalidConfig("the application name is empty"));
+            }
+            if name.contains('\0') {
+                return Err(Error::NullCharacter);
+            }
+        }
+        if let Some(target) = self.target {
+            if target.is_empty() {
+                return Err(Error::InvalidConfig("the target device type is empty"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Take the backend, or load the library.
+    fn load(&mut self) -> Result<Box<dyn Backend>, Error> {
+        Ok(match (self.backend.take(), self.library_path.take()) {
+            (Some(backend), _) => backend,
+            (None, Some(path)) => Box::new(Library::load_from(path).map_err(Error::LoadLibrary)?),
+            (None, None) => Box::new(Library::load().map_err(Error::LoadLibrary)?),
+        })
+    }
+
+    /// Validate the options, initialize the SDK, wait for it to settle,
+    /// save the lighting and set the target device, in that order.
+    pub fn build(mut self) -> Result<Driver, Error> {
+        self.validate()?;
+        let backend = self.load()?;
+
+        let mut driver = Driver::from_parts(backend, self.app_name, self.pacing, self.retry)?;
+        driver.set_recovery_policy(self.recovery);
+        driver.set_fallback(self.fallback);
+        driver.set_white_balance(self.white_balance);
+        driver.set_calibration(self.calibration);
+        driver.set_dither(self.dither);
+
+        if self.save_restore {
+            driver.save_lighting()?;
+            driver.restore_on_drop = true;
+        }
+        if let Some(target) = self.target {
+            driver.set_type(target)?;
+        }
+        Ok(driver)
+    }
+
+    /// Check whether the SDK is usable with these options, without keeping it initialized.
+    ///
+    /// Only the backend, library path, application name, pacing and retry policy are used.
+    /// See [`Driver::probe`](../struct.Driver.html#method.probe).
+    pub fn probe(mut self) -> Probe {
+        let backend = self.validate().and_then(|_| self.load());
+        probe::run(backend, &self.app_name, self.pacing, &self.retry)
+    }
+}
@@ -0,0 +1,380 @@
+//! Per-device calibration of the LED response.
+//!
+//! LEDs are far from linear, on a G910 50% looks nearly as bright as 100%, and
+//! the balance between channels differs between units. A
+//! [`Calibration`](struct.Calibration.html) set on a
+//! [`Driver`](../struct.Driver.html#method.set_calibration) corrects every
+//! color before it is converted to percent, in this order per channel:
+//! gamma, gain, black level, quantization to percent, lookup table.
+//!
+//! Calibrations are stored as text, one `name = values` line per setting:
+//!
+//! ```text
+//! # logitech-led calibration
+//! gamma = 2.2 2.2 2.4
+//! gain = 1 0.92 0.85
+//! black = 0 0 0.01
+//! ```
+//!
+//! A `lut = ` line with 3 × 101 output percentages, red first, is optional.
+//! The values for a calibration can be found with the guided
+//! [`Routine`](struct.Routine.html).
+
+use bitmap::Bitmap;
+use color::{self, Color};
+use backend::Percent3;
+use sys::BITMAP_WIDTH;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::os::raw::c_int;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Number of entries in a lookup table, one per percent.
+pub const LUT_SIZE: usize = 101;
+
+/// Lookup table from percent to percent, per channel.
+pub type Lut = [[u8; LUT_SIZE]; 3];
+
+/// Correction of the LED response, see the [module docs](index.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    /// Exponent per channel, values above 1 darken the midtones.
+    pub gamma: [f32; 3],
+    /// Scale per channel, after gamma.
+    pub gain: [f32; 3],
+    /// Lowest output per channel, everything above 0 is mapped into `black..1`.
+    pub black: [f32; 3],
+    /// Final mapping of the percentages.
+    pub lut: Option<Lut>,
+}
+
+impl Default for Calibration {
+    fn default() -> Calibration {
+        Calibration {
+            gamma: [1.0; 3],
+            gain: [1.0; 3],
+            black: [0.0; 3],
+            lut: None,
+        }
+    }
+}
+
+/// Error loading a [`Calibration`](struct.Calibration.html).
+#[derive(Debug)]
+pub enum CalibrationError {
+    Io(io::Error),
+    /// Invalid line, 1-based.
+    Parse { line: usize, message: String },
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CalibrationError::Io(ref e) => write!(f, "failed to read calibration: {}", e),
+            CalibrationError::Parse { line, ref message } =>
+                write!(f, "invalid calibration on line {}: {}", line, message),
+        }
+    }
+}
+
+impl error::Error for CalibrationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            CalibrationError::Io(ref e) => Some(e),
+            CalibrationError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CalibrationError {
+    fn from(e: io::Error) -> CalibrationError {
+        CalibrationError::Io(e)
+    }
+}
+
+impl Calibration {
+    /// Calibration that changes nothing.
+    pub fn new() -> Calibration {
+        Calibration::default()
+    }
+
+    /// Same gamma for every channel.
+    pub fn with_gamma(gamma: f32) -> Calibration {
+        Calibration { gamma: [gamma; 3], ..Calibration::default() }
+    }
+
+    /// Load a calibration saved with [`save`](#method.save).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Calibration, CalibrationError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    fn channel(&self, i: usize, v: f32) -> f32 {
+        if v <= 0.0 {
+            return 0.0;
+        }
+        let v = v.powf(self.gamma[i]) * self.gain[i];
+        self.black[i] + v * (1.0 - self.black[i])
+    }
+
+    /// `color` corrected for gamma, gain and black level, before quantization.
+    pub fn apply(&self, color: Color) -> Color {
+        let c = color.clamp();
+        Color::new(self.channel(0, c.r), self.channel(1, c.g), self.channel(2, c.b))
+    }
+
+    /// `color` fully corrected, as the percent the SDK takes.
+    pub fn to_percent(&self, color: Color) -> Percent3 {
+        self.map_percent(color::to_precent(self.apply(color)))
+    }
+
+    /// Apply the lookup table to quantized percentages.
+    pub fn map_percent(&self, p: Percent3) -> Percent3 {
+        match self.lut {
+            Some(ref lut) => {
+                let map = |i: usize, v: c_int| lut[i][v.clamp(0, 100) as usize] as c_int;
+                (map(0, p.0), map(1, p.1), map(2, p.2))
+            },
+            None => p,
+        }
+    }
+}
+
+fn write_values<T: fmt::Display>(f: &mut fmt::Formatter, name: &str, values: &[T]) -> fmt::Result {
+    write!(f, "{} =", name)?;
+    for v in values {
+        write!(f, " {}", v)?;
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# logitech-led calibration")?;
+        write_values(f, "gamma", &self.gamma)?;
+        write_values(f, "gain", &self.gain)?;
+        write_values(f, "black", &self.black)?;
+        if let Some(ref lut) = self.lut {
+            let values: Vec<u8> = lut.iter().flat_map(|c| c.iter().cloned()).collect();
+            write_values(f, "lut", &values)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_values<T: FromStr>(line: usize, values: &str, n: usize) -> Result<Vec<T>, CalibrationError> {
+    let err = |message: String| CalibrationError::Parse { line, message };
+    let v = values.split_whitespace()
+        .map(|s| s.parse().map_err(|_| err(format!("invalid number {:?}", s))))
+        .collect::<Result<Vec<T>, _>>()?;
+    if v.len() != n {
+        return Err(err(format!("expected {} values, found {}", n, v.len())));
+    }
+    Ok(v)
+}
+
+fn parse_channels(line: usize, values: &str) -> Result<[f32; 3], CalibrationError> {
+    let v = parse_values::<f32>(line, values, 3)?;
+    if v.iter().any(|n| !n.is_finite() || *n < 0.0) {
+        return Err(CalibrationError::Parse { line, message: "values must be positive".to_owned() });
+    }
+    Ok([v[0], v[1], v[2]])
+}
+
+impl FromStr for Calibration {
+    type Err = CalibrationError;
+
+    fn from_str(s: &str) -> Result<Calibration, CalibrationError> {
+        let mut cal = Calibration::default();
+
+        for (i, line) in s.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, values) = match line.find('=') {
+                Some(eq) => (line[..eq].trim(), &line[eq + 1..]),
+                None => return Err(CalibrationError::Parse {
+                    line: line_no,
+                    message: "expected `name = values`".to_owned(),
+                }),
+            };
+            match name {
+                "gamma" => cal.gamma = parse_channels(line_no, values)?,
+                "gain" => cal.gain = parse_channels(line_no, values)?,
+                "black" => cal.black = parse_channels(line_no, values)?,
+                "lut" => {
+                    let v = parse_values::<u8>(line_no, values, 3 * LUT_SIZE)?;
+                    if v.iter().any(|&p| p > 100) {
+                        return Err(CalibrationError::Parse {
+                            line: line_no,
+                            message: "lookup table values must be percentages".to_owned(),
+                        });
+                    }
+                    let mut lut = [[0; LUT_SIZE]; 3];
+                    for (c, chunk) in lut.iter_mut().zip(v.chunks(LUT_SIZE)) {
+                        c.copy_from_slice(chunk);
+                    }
+                    cal.lut = Some(lut);
+                },
+                _ => return Err(CalibrationError::Parse {
+                    line: line_no,
+                    message: format!("unknown setting {:?}", name),
+                }),
+            }
+        }
+        Ok(cal)
+    }
+}
+
+/// One step of a calibration [`Routine`](struct.Routine.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Match the brightness of a solid channel to a half on, half off pattern.
+    /// The channel index is 0 for red, 1 for green and 2 for blue.
+    Gamma(usize),
+    /// Turn down a channel until white looks neutral.
+    Gain(usize),
+    Done,
+}
+
+const STEPS: [Step; 6] = [
+    Step::Gamma(0), Step::Gamma(1), Step::Gamma(2),
+    Step::Gain(0), Step::Gain(1), Step::Gain(2),
+];
+
+const CHANNEL_NAMES: [&str; 3] = ["red", "green", "blue"];
+
+/// Guided calibration, driven by a user interface.
+///
+/// Each step shows a test pattern. The user adjusts the level until the
+/// pattern looks right, then confirms and the routine moves on:
+///
+/// ```no_run
+/// # extern crate logitech_led as led;
+/// # use led::Driver;
+/// # use led::calibration::{Routine, Step};
+/// # fn read_input() -> char { '\n' }
+/// # fn main() {
+/// let mut driver = Driver::init().unwrap();
+/// let mut routine = Routine::new();
+/// while routine.step() != Step::Done {
+///     driver.set_lighting_from_bitmap(&routine.pattern()).unwrap();
+///     println!("{}", routine.instructions());
+///     match read_input() {
+///         '+' => routine.adjust(0.01),
+///         '-' => routine.adjust(-0.01),
+///         _ => routine.confirm(),
+///     }
+/// }
+/// routine.calibration().save("keyboard.cal").unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Routine {
+    step: usize,
+    level: f32,
+    calibration: Calibration,
+}
+
+impl Default for Routine {
+    fn default() -> Routine {
+        Routine::new()
+    }
+}
+
+impl Routine {
+    pub fn new() -> Routine {
+        Routine { step: 0, level: 0.5, calibration: Calibration::default() }
+    }
+
+    pub fn step(&self) -> Step {
+        STEPS.get(self.step).cloned().unwrap_or(Step::Done)
+    }
+
+    /// The level the user adjusts in the current step.
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Change the level by `delta`, keeping it in `0.01..=1`.
+    pub fn adjust(&mut self, delta: f32) {
+        self.level = (self.level + delta).clamp(0.01, 1.0);
+    }
+
+    /// What the user should do in the current step.
+    pub fn instructions(&self) -> String {
+        match self.step() {
+            Step::Gamma(c) => format!(
+                "Look at the keyboard from a distance. Adjust the {} level until the right half \
+                 is as bright as the checkered left half, then confirm. Level: {:.0}%",
+                CHANNEL_NAMES[c], self.level * 100.0),
+            Step::Gain(c) => format!(
+                "Turn down {} until the keyboard looks neutral white, then confirm. Level: {:.0}%",
+                CHANNEL_NAMES[c], self.level * 100.0),
+            Step::Done => "Calibration done.".to_owned(),
+        }
+    }
+
+    /// Test pattern for the current step, to send with a driver that has no
+    /// calibration or white balance set, so it reaches the LEDs as is.
+    pub fn pattern(&self) -> Bitmap {
+        let mut bitmap = Bitmap::new();
+        match self.step() {
+            Step::Gamma(c) => {
+                let full = channel_color(c, 1.0);
+                let level = channel_color(c, self.level);
+                bitmap.paint(|x, y| {
+                    if x >= BITMAP_WIDTH / 2 { level }
+                    else if (x + y) % 2 == 0 { full }
+                    else { Color::BLACK }
+                });
+            },
+            Step::Gain(c) => {
+                let mut gain = self.calibration.gain;
+                gain[c] = self.level;
+                bitmap.fill(Color::new(gain[0], gain[1], gain[2]));
+            },
+            Step::Done => {},
+        }
+        bitmap
+    }
+
+    /// Accept the current level and move to the next step.
+    pub fn confirm(&mut self) {
+        match self.step() {
+            // The half on pattern emits half the light, so the LED emits half
+            // at `level`. The gamma has to map 0.5 to `level`: 0.5 ^ gamma = level.
+            Step::Gamma(c) => {
+                let level = self.level.clamp(0.01, 0.99);
+                self.calibration.gamma[c] = level.ln() / 0.5f32.ln();
+            },
+            Step::Gain(c) => self.calibration.gain[c] = self.level,
+            Step::Done => return,
+        }
+        self.step += 1;
+        self.level = match self.step() {
+            Step::Gain(_) => 1.0,
+            _ => 0.5,
+        };
+    }
+
+    /// The calibration found so far.
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+}
+
+fn channel_color(channel: usize, v: f32) -> Color {
+    let mut c = [0.0; 3];
+    c[channel] = v;
+    Color::from(c)
+}
@@ -0,0 +1,116 @@
+//! Colors with straight alpha, for overlays.
+//!
+//! [`Color`](../struct.Color.html) is always opaque. Converting a BGRA cell
+//! to a `Color` premultiplies, giving the cell as it looks composited onto
+//! black, and converting a `Color` back writes alpha 255. `ColorA` keeps
+//! alpha separate from the color instead, so partly transparent overlays
+//! can be layered onto a base without darkening it.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::{Color, BGRA, BlendMode};
+//! use led::color::ColorA;
+//!
+//! # fn main() {
+//! let overlay = ColorA::from([0, 0, 255, 128]);
+//! assert_eq!(overlay.color(), Color::RED);
+//! assert!(Color::from([0, 0, 255, 128]).approx_eq(&overlay.premultiplied(), 1e-6));
+//!
+//! let out = overlay.over(Color::BLUE.with_alpha(1.0), BlendMode::Normal);
+//! assert_eq!(BGRA::from(out), [127, 0, 128, 255]);
+//! # }
+//! ```
+
+use super::{Color, BGRA, BlendMode, PorterDuff, EPSILON, clampf, f2u8};
+
+/// RGB color with straight, not premultiplied, alpha. Components are in [0,1].
+#[derive(Debug, Copy, Clone)]
+pub struct ColorA {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    /// Coverage, 0 is fully transparent.
+    pub a: f32,
+}
+
+impl ColorA {
+    pub const TRANSPARENT: ColorA = ColorA { r: 0.0, g: 0.0, b: 0.0, a: 0.0 };
+
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> ColorA {
+        ColorA { r: clampf(r), g: clampf(g), b: clampf(b), a: clampf(a) }
+    }
+
+    /// Straight color from a premultiplied `color` with alpha `a`, black if `a` is 0.
+    pub fn from_premultiplied(color: Color, a: f32) -> ColorA {
+        let a = clampf(a);
+        if a <= 0.0 {
+            return ColorA::TRANSPARENT;
+        }
+        ColorA::new(color.r / a, color.g / a, color.b / a, a)
+    }
+
+    /// The color without its alpha.
+    pub fn color(&self) -> Color {
+        Color { r: self.r, g: self.g, b: self.b }
+    }
+
+    /// The color multiplied by its alpha, the same as composited onto black.
+    pub fn premultiplied(&self) -> Color {
+        Color::new(self.r * self.a, self.g * self.a, self.b * self.a)
+    }
+
+    /// Composite `self` onto `base`, blending the colors with `mode` where both are covered.
+    pub fn over(self, base: ColorA, mode: BlendMode) -> ColorA {
+        let top = self.color();
+        let blended = mode.apply(base.color(), top);
+        let mixed = top * (1.0 - base.a) + blended * base.a;
+        let (c, a) = PorterDuff::SrcOver.composite(mixed, self.a, base.color(), base.a);
+        c.with_alpha(a)
+    }
+
+    /// Compare within `eps` per component, alpha included.
+    pub fn approx_eq(&self, other: &ColorA, eps: f32) -> bool {
+        self.color().approx_eq(&other.color(), eps) && (self.a - other.a).abs() <= eps
+    }
+}
+
+impl Color {
+    /// The color with alpha `a`.
+    pub fn with_alpha(&self, a: f32) -> ColorA {
+        let c = self.clamp();
+        ColorA { r: c.r, g: c.g, b: c.b, a: clampf(a) }
+    }
+}
+
+impl Default for ColorA {
+    fn default() -> ColorA {
+        ColorA::TRANSPARENT
+    }
+}
+
+/// Compares within [`EPSILON`](../constant.EPSILON.html).
+impl PartialEq for ColorA {
+    fn eq(&self, other: &ColorA) -> bool {
+        self.approx_eq(other, EPSILON)
+    }
+}
+
+impl From<Color> for ColorA {
+    fn from(c: Color) -> ColorA {
+        c.with_alpha(1.0)
+    }
+}
+
+/// Straight alpha, the color bytes are taken as they are.
+impl From<BGRA> for ColorA {
+    fn from(bgra: [u8; 4]) -> ColorA {
+        let n = |v: u8| v as f32 / 255.0;
+        ColorA { r: n(bgra[2]), g: n(bgra[1]), b: n(bgra[0]), a: n(bgra[3]) }
+    }
+}
+
+impl From<ColorA> for BGRA {
+    fn from(c: ColorA) -> [u8; 4] {
+        [f2u8(c.b), f2u8(c.g), f2u8(c.r), f2u8(c.a)]
+    }
+}
@@ -0,0 +1,135 @@
+//! Blend modes and Porter-Duff compositing.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::Color;
+//! use led::color::{BlendMode, PorterDuff};
+//!
+//! # fn main() {
+//! let base = Color::new(0.2, 0.4, 0.8);
+//! assert_eq!(base.blend(Color::WHITE, BlendMode::Multiply), base);
+//! assert_eq!(BlendMode::Screen.apply(base, Color::BLACK), base);
+//!
+//! let (c, a) = PorterDuff::SrcOver.composite(Color::RED, 0.5, Color::BLUE, 1.0);
+//! assert_eq!((c, a), (Color::new(0.5, 0.0, 0.5), 1.0));
+//! # }
+//! ```
+
+use super::{Color, clampf};
+
+/// How a top color is combined with a base color, per channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    /// The top color replaces the base.
+    #[default]
+    Normal,
+    /// `base * top`, darkens.
+    Multiply,
+    /// `1 - (1 - base) * (1 - top)`, lightens.
+    Screen,
+    /// Multiply for dark base channels and screen for light ones.
+    Overlay,
+    /// `base + top`, saturating.
+    Add,
+    /// `base - top`, saturating.
+    Subtract,
+    /// The darker of the two.
+    Darken,
+    /// The lighter of the two.
+    Lighten,
+    /// `|base - top|`.
+    Difference,
+}
+
+impl BlendMode {
+    fn channel(self, b: f32, t: f32) -> f32 {
+        match self {
+            BlendMode::Normal => t,
+            BlendMode::Multiply => b * t,
+            BlendMode::Screen => 1.0 - (1.0 - b) * (1.0 - t),
+            BlendMode::Overlay => {
+                if b <= 0.5 { 2.0 * b * t }
+                else { 1.0 - 2.0 * (1.0 - b) * (1.0 - t) }
+            },
+            BlendMode::Add => b + t,
+            BlendMode::Subtract => b - t,
+            BlendMode::Darken => b.min(t),
+            BlendMode::Lighten => b.max(t),
+            BlendMode::Difference => (b - t).abs(),
+        }
+    }
+
+    /// Blend `top` onto `base`.
+    pub fn apply(self, base: Color, top: Color) -> Color {
+        let (b, t) = (base.clamp(), top.clamp());
+        Color::new(
+            self.channel(b.r, t.r),
+            self.channel(b.g, t.g),
+            self.channel(b.b, t.b),
+        )
+    }
+
+    /// Blend `top` onto `base`, and mix the result with `base` by `opacity`.
+    pub fn apply_with_opacity(self, base: Color, top: Color, opacity: f32) -> Color {
+        base.lerp(self.apply(base, top), opacity)
+    }
+}
+
+/// Porter-Duff compositing operators, for a source over a destination with coverage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PorterDuff {
+    Clear,
+    Src,
+    Dst,
+    SrcOver,
+    DstOver,
+    SrcIn,
+    DstIn,
+    SrcOut,
+    DstOut,
+    SrcAtop,
+    DstAtop,
+    Xor,
+}
+
+impl PorterDuff {
+    /// Fractions of the source and destination kept, for the given alphas.
+    fn factors(self, sa: f32, da: f32) -> (f32, f32) {
+        match self {
+            PorterDuff::Clear => (0.0, 0.0),
+            PorterDuff::Src => (1.0, 0.0),
+            PorterDuff::Dst => (0.0, 1.0),
+            PorterDuff::SrcOver => (1.0, 1.0 - sa),
+            PorterDuff::DstOver => (1.0 - da, 1.0),
+            PorterDuff::SrcIn => (da, 0.0),
+            PorterDuff::DstIn => (0.0, sa),
+            PorterDuff::SrcOut => (1.0 - da, 0.0),
+            PorterDuff::DstOut => (0.0, 1.0 - sa),
+            PorterDuff::SrcAtop => (da, 1.0 - sa),
+            PorterDuff::DstAtop => (1.0 - da, sa),
+            PorterDuff::Xor => (1.0 - da, 1.0 - sa),
+        }
+    }
+
+    /// Composite `src` with alpha `src_alpha` onto `dst` with alpha `dst_alpha`.
+    ///
+    /// Colors are straight, not premultiplied. Returns the straight color and alpha
+    /// of the result, black if the result is fully transparent.
+    pub fn composite(self, src: Color, src_alpha: f32, dst: Color, dst_alpha: f32) -> (Color, f32) {
+        let (sa, da) = (clampf(src_alpha), clampf(dst_alpha));
+        let (fs, fd) = self.factors(sa, da);
+        let (ws, wd) = (fs * sa, fd * da);
+
+        let a = ws + wd;
+        if a <= 0.0 {
+            return (Color::BLACK, 0.0);
+        }
+        let (s, d) = (src.clamp(), dst.clamp());
+        let c = Color::new(
+            (s.r * ws + d.r * wd) / a,
+            (s.g * ws + d.g * wd) / a,
+            (s.b * ws + d.b * wd) / a,
+        );
+        (c, clampf(a))
+    }
+}
@@ -0,0 +1,217 @@
+//! Color gradients with stops, interpolation spaces and easing.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::Color;
+//! use led::color::{Gradient, Interpolation, Easing};
+//!
+//! # fn main() {
+//! let heat = Gradient::even(&[Color::BLACK, Color::RED, Color::YELLOW, Color::WHITE])
+//!     .interpolation(Interpolation::Oklab)
+//!     .easing(Easing::Smoothstep);
+//! assert_eq!(heat.sample(0.0), Color::BLACK);
+//! assert_eq!(heat.sample(1.0), Color::WHITE);
+//!
+//! let rainbow = Gradient::rainbow();
+//! assert!(rainbow.sample(1.0 / 3.0).approx_eq(&Color::LIME, 1e-4));
+//! # }
+//! ```
+
+use super::{Color, Oklab, LinearRgb, clampf, normalize_hue};
+
+/// Color space a [`Gradient`](struct.Gradient.html) interpolates in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Interpolation {
+    /// Gamma encoded sRGB, like [`Color::lerp`](../struct.Color.html#method.lerp).
+    #[default]
+    Rgb,
+    /// Linear light, physically correct mixing.
+    Linear,
+    /// HSV, along the shorter way around the hue circle.
+    Hsv,
+    /// Oklab, perceptually even steps.
+    Oklab,
+}
+
+/// Easing applied between each pair of stops.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Quadratic, slow start.
+    EaseIn,
+    /// Quadratic, slow end.
+    EaseOut,
+    /// Quadratic, slow start and end.
+    EaseInOut,
+    /// `3t² - 2t³`.
+    Smoothstep,
+    /// Hard steps, `n` colors per segment.
+    Steps(u32),
+}
+
+impl Easing {
+    /// Map `t` in [0,1] to an eased value in [0,1].
+    pub fn apply(self, t: f32) -> f32 {
+        let t = clampf(t);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => {
+                if t < 0.5 { 2.0 * t * t }
+                else { 1.0 - 2.0 * (1.0 - t) * (1.0 - t) }
+            },
+            Easing::Smoothstep => t * t * (3.0 - 2.0 * t),
+            Easing::Steps(n) => {
+                let n = n.max(1) as f32;
+                if n <= 1.0 { 0.0 } else { ((t * n).floor() / (n - 1.0)).min(1.0) }
+            },
+        }
+    }
+}
+
+/// Colors at positions in [0,1], sampled by interpolating between the nearest stops.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(from = "GradientData"))]
+pub struct Gradient {
+    stops: Vec<(f32, Color)>,
+    interpolation: Interpolation,
+    easing: Easing,
+}
+
+/// A deserialized [`Gradient`](struct.Gradient.html), before its stops are sorted and clamped.
+#[cfg(feature = "serde")]
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct GradientData {
+    stops: Vec<(f32, Color)>,
+    interpolation: Interpolation,
+    easing: Easing,
+}
+
+#[cfg(feature = "serde")]
+impl From<GradientData> for Gradient {
+    fn from(data: GradientData) -> Gradient {
+        Gradient::new(&data.stops).interpolation(data.interpolation).easing(data.easing)
+    }
+}
+
+impl Gradient {
+    /// Gradient through `stops`, given as `(position, color)` in any order.
+    pub fn new(stops: &[(f32, Color)]) -> Gradient {
+        let mut g = Gradient::default();
+        for &(t, c) in stops {
+            g.add_stop(t, c);
+        }
+        g
+    }
+
+    /// Gradient through `colors`, spaced evenly.
+    pub fn even(colors: &[Color]) -> Gradient {
+        let n = colors.len().saturating_sub(1).max(1) as f32;
+        let stops: Vec<_> = colors.iter().enumerate().map(|(i, &c)| (i as f32 / n, c)).collect();
+        Gradient::new(&stops)
+    }
+
+    /// Full hue circle, red to red, interpolated in HSV.
+    pub fn rainbow() -> Gradient {
+        Gradient::even(&[Color::RED, Color::LIME, Color::BLUE, Color::RED])
+            .interpolation(Interpolation::Hsv)
+    }
+
+    /// Black through red and yellow to white.
+    pub fn heat() -> Gradient {
+        Gradient::even(&[Color::BLACK, Color::RED, Color::YELLOW, Color::WHITE])
+    }
+
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Gradient {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Gradient {
+        self.easing = easing;
+        self
+    }
+
+    /// Add a stop at `position`, clamped to [0,1]. Stops at the same position
+    /// keep their insertion order, which makes a hard edge.
+    pub fn add_stop(&mut self, position: f32, color: Color) {
+        let t = clampf(position);
+        let i = self.stops.iter().position(|&(p, _)| p > t).unwrap_or(self.stops.len());
+        self.stops.insert(i, (t, color));
+    }
+
+    /// The stops, sorted by position.
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// Color at `t` in [0,1]. Black if there are no stops.
+    pub fn sample(&self, t: f32) -> Color {
+        let t = clampf(t);
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return Color::BLACK,
+        };
+        if t <= first.0 {
+            return first.1;
+        }
+        if t >= last.0 {
+            return last.1;
+        }
+
+        let i = self.stops.iter().position(|&(p, _)| p > t).unwrap_or(self.stops.len() - 1);
+        let (p0, c0) = self.stops[i - 1];
+        let (p1, c1) = self.stops[i];
+        let local = if p1 > p0 { (t - p0) / (p1 - p0) } else { 1.0 };
+        interpolate(c0, c1, self.easing.apply(local), self.interpolation)
+    }
+
+    /// `n` evenly spaced samples, from `t = 0` to `t = 1`.
+    pub fn samples(&self, n: usize) -> Vec<Color> {
+        let d = n.saturating_sub(1).max(1) as f32;
+        (0..n).map(|i| self.sample(i as f32 / d)).collect()
+    }
+}
+
+/// Interpolate from `a` to `b` in `space`.
+pub fn interpolate(a: Color, b: Color, t: f32, space: Interpolation) -> Color {
+    match space {
+        Interpolation::Rgb => a.lerp(b, t),
+        Interpolation::Linear => {
+            let (a, b) = (a.to_linear(), b.to_linear());
+            Color::from_linear(LinearRgb {
+                r: a.r + (b.r - a.r) * t,
+                g: a.g + (b.g - a.g) * t,
+                b: a.b + (b.b - a.b) * t,
+            })
+        },
+        Interpolation::Hsv => {
+            let (h0, s0, v0) = a.to_hsv();
+            let (h1, s1, v1) = b.to_hsv();
+            // Grays have no hue, keep the hue of the other color.
+            let h0 = if s0 <= 0.0 { h1 } else { h0 };
+            let h1 = if s1 <= 0.0 { h0 } else { h1 };
+
+            let mut dh = h1 - h0;
+            if dh > 180.0 {
+                dh -= 360.0;
+            } else if dh < -180.0 {
+                dh += 360.0;
+            }
+            Color::from_hsv(normalize_hue(h0 + dh * t), s0 + (s1 - s0) * t, v0 + (v1 - v0) * t)
+        },
+        Interpolation::Oklab => {
+            let (a, b): (Oklab, Oklab) = (a.to_oklab(), b.to_oklab());
+            Color::from_oklab(Oklab {
+                l: a.l + (b.l - a.l) * t,
+                a: a.a + (b.a - a.a) * t,
+                b: a.b + (b.b - a.b) * t,
+            })
+        },
+    }
+}
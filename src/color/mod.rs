@@ -0,0 +1,270 @@
+//! Colors and conversions to the SDK formats.
+
+use std::os::raw::c_int;
+use std::ops::{Add, Sub, Mul, AddAssign, SubAssign, MulAssign};
+use std::fmt;
+
+pub mod monochrome;
+pub mod blend;
+pub mod parse;
+pub mod spaces;
+pub mod gradient;
+pub mod temperature;
+pub mod quantize;
+pub mod alpha;
+
+pub use self::blend::{BlendMode, PorterDuff};
+pub use self::parse::ParseColorError;
+pub use self::spaces::{LinearRgb, Xyz, Oklab, Oklch};
+pub use self::gradient::{Gradient, Interpolation, Easing};
+pub use self::temperature::{WhitePoint, WhiteBalance};
+pub use self::quantize::{Percent, PERCENT_ERROR, BYTE_ERROR};
+pub use self::alpha::ColorA;
+
+use self::quantize::f2u8;
+
+/// Largest difference per channel for two colors to compare equal.
+///
+/// Well below the 1/255 step of a bitmap, so colors that compare equal
+/// are sent to the SDK as the same color.
+pub const EPSILON: f32 = 1e-5;
+
+/// RGB color, every operation keeps the components in [0,1].
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Color {
+    /// Red component [0,1]
+    pub r: f32,
+    /// Green component [0,1]
+    pub g: f32,
+    /// Blue component [0,1]
+    pub b: f32,
+}
+
+pub type BGRA = [u8; 4];
+
+fn clampf(n: f32) -> f32 {
+    if !n.is_normal() || n < 0.0 { 0.0 }
+    else if n > 1.0 { 1.0 }
+    else { n }
+}
+
+/// Wrap a hue in degrees to [0,360).
+fn normalize_hue(h: f32) -> f32 {
+    let h = h % 360.0;
+    let h = if h < 0.0 { h + 360.0 } else { h };
+    if h >= 360.0 { 0.0 } else { h }
+}
+
+fn hsv2rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    if s <= 0.0 {
+        return (v,v,v);
+    }
+    let hh = normalize_hue(h) / 60.0;
+    let i = hh.floor() as u32;
+    let ff = hh - i as f32;
+    let p = v * (1.0 - s);
+    let q = v * (1.0 - (s * ff));
+    let t = v * (1.0 - (s * (1.0 - ff)));
+    match i {
+        0 => (v,t,p),
+        1 => (q,v,p),
+        2 => (p,v,t),
+        3 => (p,q,v),
+        4 => (t,p,v),
+        5 => (v,p,q),
+        _ => panic!("Unexpected value in hsv2rgb: i: {} h: {}", i, h),
+    }
+}
+
+fn hsl2rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    let (s, l) = (clampf(s), clampf(l));
+    let v = l + s * l.min(1.0 - l);
+    let sv = if v <= 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+    hsv2rgb(h, sv, v)
+}
+
+/// The named colors have their CSS values, the same as parsing their names.
+impl Color {
+    pub const BLACK: Color = Color { r: 0.0, g: 0.0, b: 0.0 };
+    pub const WHITE: Color = Color { r: 1.0, g: 1.0, b: 1.0 };
+    pub const RED: Color = Color { r: 1.0, g: 0.0, b: 0.0 };
+    /// Full green. As in CSS, [`GREEN`](#associatedconstant.GREEN) is darker.
+    pub const LIME: Color = Color { r: 0.0, g: 1.0, b: 0.0 };
+    pub const GREEN: Color = Color { r: 0.0, g: 128.0 / 255.0, b: 0.0 };
+    pub const BLUE: Color = Color { r: 0.0, g: 0.0, b: 1.0 };
+    pub const YELLOW: Color = Color { r: 1.0, g: 1.0, b: 0.0 };
+    pub const CYAN: Color = Color { r: 0.0, g: 1.0, b: 1.0 };
+    pub const MAGENTA: Color = Color { r: 1.0, g: 0.0, b: 1.0 };
+    pub const ORANGE: Color = Color { r: 1.0, g: 165.0 / 255.0, b: 0.0 };
+    pub const PURPLE: Color = Color { r: 128.0 / 255.0, g: 0.0, b: 128.0 / 255.0 };
+
+    pub fn new(r: f32, g: f32, b: f32) -> Color {
+        Color{ r, g, b }.clamp()
+    }
+
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Color {
+        hsv2rgb(h, s, v).into()
+    }
+
+    pub fn clamp(&self) -> Color {
+        let mut c = *self;
+        c.clamp_mut();
+        c
+    }
+
+    pub fn clamp_mut(&mut self) {
+        self.r = clampf(self.r);
+        self.g = clampf(self.g);
+        self.b = clampf(self.b);
+    }
+
+    /// Returns `true` if every channel differs by at most `epsilon`.
+    pub fn approx_eq(&self, other: &Color, epsilon: f32) -> bool {
+        (self.r - other.r).abs() <= epsilon &&
+        (self.g - other.g).abs() <= epsilon &&
+        (self.b - other.b).abs() <= epsilon
+    }
+
+    /// Linear interpolation from `self` at `t = 0` to `other` at `t = 1`.
+    pub fn lerp(&self, other: Color, t: f32) -> Color {
+        let t = clampf(t);
+        Color::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+        )
+    }
+
+    /// The color as `#rrggbb`.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", f2u8(self.r), f2u8(self.g), f2u8(self.b))
+    }
+
+    /// Blend `top` onto `self` with `mode`, see [`BlendMode`](blend/enum.BlendMode.html).
+    pub fn blend(&self, top: Color, mode: BlendMode) -> Color {
+        mode.apply(*self, top)
+    }
+}
+
+/// Formats as `#rrggbb`, the inverse of [`FromStr`](parse/index.html).
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// Compares within [`EPSILON`](constant.EPSILON.html).
+impl PartialEq for Color {
+    fn eq(&self, other: &Color) -> bool {
+        self.approx_eq(other, EPSILON)
+    }
+}
+
+/// Saturating addition.
+impl Add for Color {
+    type Output = Color;
+    fn add(self, o: Color) -> Color {
+        Color::new(self.r + o.r, self.g + o.g, self.b + o.b)
+    }
+}
+
+/// Saturating subtraction.
+impl Sub for Color {
+    type Output = Color;
+    fn sub(self, o: Color) -> Color {
+        Color::new(self.r - o.r, self.g - o.g, self.b - o.b)
+    }
+}
+
+/// Scale every channel, clamped to [0,1].
+impl Mul<f32> for Color {
+    type Output = Color;
+    fn mul(self, k: f32) -> Color {
+        Color::new(self.r * k, self.g * k, self.b * k)
+    }
+}
+
+/// Channel-wise product, the same as [`BlendMode::Multiply`](blend/enum.BlendMode.html).
+impl Mul for Color {
+    type Output = Color;
+    fn mul(self, o: Color) -> Color {
+        Color::new(self.r * o.r, self.g * o.g, self.b * o.b)
+    }
+}
+
+impl AddAssign for Color {
+    fn add_assign(&mut self, o: Color) {
+        *self = *self + o;
+    }
+}
+
+impl SubAssign for Color {
+    fn sub_assign(&mut self, o: Color) {
+        *self = *self - o;
+    }
+}
+
+impl MulAssign<f32> for Color {
+    fn mul_assign(&mut self, k: f32) {
+        *self = *self * k;
+    }
+}
+
+/// Premultiplies, giving the cell as it looks composited onto black.
+/// Use [`ColorA`](alpha/struct.ColorA.html) to keep the alpha.
+impl From<BGRA> for Color {
+    fn from(bgra: [u8; 4]) -> Color {
+        let a = bgra[3] as f32 / 255.0;
+        Color{
+            r:  (bgra[2] as f32 / 255.0) * a,
+            g:  (bgra[1] as f32 / 255.0) * a,
+            b:  (bgra[0] as f32 / 255.0) * a,
+        }.clamp()
+    }
+}
+
+/// Always opaque, alpha is 255.
+impl From<Color> for BGRA {
+    fn from(c: Color) -> [u8; 4] {
+        [f2u8(c.b), f2u8(c.g), f2u8(c.r), 255]
+    }
+}
+
+
+impl From<(f32, f32, f32)> for Color {
+    fn from(t: (f32, f32, f32)) -> Color {
+        Color{ r: t.0, g: t.1, b: t.2, }.clamp()
+    }
+}
+
+impl From<Color> for (f32, f32, f32) {
+    fn from(mut c: Color) -> (f32, f32, f32) {
+        c.clamp_mut();
+        (c.r, c.g, c.b)
+    }
+}
+
+impl From<[f32; 3]> for Color {
+    fn from(t: [f32; 3]) -> Color {
+        Color{ r: t[0], g: t[1], b: t[2] }.clamp()
+    }
+}
+
+impl From<Color> for [f32; 3] {
+    fn from(mut c: Color) -> [f32; 3] {
+        c.clamp_mut();
+        [c.r, c.g, c.b]
+    }
+}
+
+/// Nearest whole percentages of `c`, see [`quantize`](quantize/index.html).
+pub fn to_precent(c: Color) -> (c_int, c_int, c_int) {
+    let p = c.to_percent();
+    (p[0].into(), p[1].into(), p[2].into())
+}
+
+/// Exact inverse of [`to_precent`](fn.to_precent.html), out of range percentages are clamped.
+pub fn from_precent(p: (c_int, c_int, c_int)) -> Color {
+    Color::from_percent([Percent::from_c_int(p.0), Percent::from_c_int(p.1), Percent::from_c_int(p.2)])
+}
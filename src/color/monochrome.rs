@@ -0,0 +1,131 @@
+//! How colors look on single color devices.
+//!
+//! Monochrome devices like the G710+, G105 and G610 show the highest of the
+//! R, G and B values, so a hue rotation at full brightness looks constant on
+//! them. These helpers measure how visible a color sequence is on such a
+//! device, and add brightness modulation to it while keeping the hues.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::Color;
+//! use led::color::monochrome;
+//!
+//! # fn main() {
+//! let rainbow: Vec<Color> = (0..36).map(|i| Color::from_hsv(i as f32 * 10.0, 1.0, 1.0)).collect();
+//! assert!(!monochrome::analyze(rainbow.iter().cloned()).is_visible());
+//!
+//! let modulated = monochrome::modulate(&rainbow, 0.8);
+//! assert!(monochrome::analyze(modulated.iter().cloned()).is_visible());
+//! # }
+//! ```
+
+use super::{Color, clampf};
+use std::f32::consts::PI;
+
+/// Smallest brightness range, as a fraction of full brightness, that is
+/// considered noticeable by [`Visibility::is_visible`](struct.Visibility.html#method.is_visible).
+pub const VISIBILITY_THRESHOLD: f32 = 0.1;
+
+/// Brightness a single color device shows for `color`.
+pub fn level(color: Color) -> f32 {
+    let c = color.clamp();
+    c.r.max(c.g).max(c.b)
+}
+
+/// `color` as shown by a single color device, as a gray.
+pub fn preview_monochrome(color: Color) -> Color {
+    let l = level(color);
+    Color::new(l, l, l)
+}
+
+/// Brightness statistics of a color sequence on a single color device.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Visibility {
+    /// Lowest brightness.
+    pub min: f32,
+    /// Highest brightness.
+    pub max: f32,
+    /// Largest brightness change between two consecutive colors.
+    pub max_step: f32,
+    /// Number of colors analyzed.
+    pub samples: usize,
+}
+
+impl Visibility {
+    /// Difference between the highest and lowest brightness.
+    pub fn range(&self) -> f32 {
+        self.max - self.min
+    }
+
+    /// Whether the brightness changes by at least
+    /// [`VISIBILITY_THRESHOLD`](constant.VISIBILITY_THRESHOLD.html).
+    pub fn is_visible(&self) -> bool {
+        self.is_visible_with(VISIBILITY_THRESHOLD)
+    }
+
+    /// Whether the brightness changes by at least `threshold`.
+    pub fn is_visible_with(&self, threshold: f32) -> bool {
+        self.samples > 1 && self.range() >= threshold
+    }
+}
+
+/// Measure how much a color sequence changes on a single color device.
+pub fn analyze<I: IntoIterator<Item = Color>>(colors: I) -> Visibility {
+    let mut v = Visibility { min: 1.0, max: 0.0, max_step: 0.0, samples: 0 };
+    let mut prev: Option<f32> = None;
+
+    for c in colors {
+        let l = level(c);
+        v.min = v.min.min(l);
+        v.max = v.max.max(l);
+        if let Some(p) = prev {
+            v.max_step = v.max_step.max((l - p).abs());
+        }
+        prev = Some(l);
+        v.samples += 1;
+    }
+
+    if v.samples == 0 {
+        v.min = 0.0;
+    }
+    v
+}
+
+/// Measure an effect given as a function of time, sampled `samples` times over `[0, 1]`.
+pub fn analyze_fn<F: FnMut(f32) -> Color>(mut effect: F, samples: usize) -> Visibility {
+    let n = samples.max(2);
+    analyze((0..n).map(|i| effect(i as f32 / (n - 1) as f32)))
+}
+
+/// `color` with the same hue and saturation at brightness `level`.
+///
+/// Black has no hue and becomes a gray.
+pub fn with_level(color: Color, level: f32) -> Color {
+    let current = self::level(color);
+    if current <= 0.0 {
+        return Color::new(level, level, level);
+    }
+    let k = level / current;
+    let c = color.clamp();
+    Color::new(c.r * k, c.g * k, c.b * k)
+}
+
+/// Brightness modulation at `phase` in `[0, 1]` of a cycle.
+///
+/// Full brightness at the ends of the cycle, dimmed by `depth` in the middle.
+pub fn modulate_at(color: Color, phase: f32, depth: f32) -> Color {
+    let depth = clampf(depth);
+    let dip = 0.5 - 0.5 * (2.0 * PI * phase).cos();
+    with_level(color, level(color) * (1.0 - depth * dip))
+}
+
+/// Add one cycle of brightness modulation over `colors`, keeping their hues.
+///
+/// `depth` is how far the brightness dips, `0.0` leaves the colors unchanged
+/// and `1.0` fades to black halfway through the sequence.
+pub fn modulate(colors: &[Color], depth: f32) -> Vec<Color> {
+    let n = colors.len() as f32;
+    colors.iter().enumerate()
+        .map(|(i, &c)| modulate_at(c, i as f32 / n, depth))
+        .collect()
+}
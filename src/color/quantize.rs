@@ -0,0 +1,99 @@
+//! Quantization of colors to the formats the SDK takes.
+//!
+//! The SDK takes colors as whole percentages, 101 levels per channel, and
+//! bitmaps as bytes, 256 levels per channel. Both conversions round to the
+//! nearest level, so the error per channel is at most half a step:
+//! [`PERCENT_ERROR`](constant.PERCENT_ERROR.html) and
+//! [`BYTE_ERROR`](constant.BYTE_ERROR.html). Converting back is exact, so a
+//! quantized color survives any number of round trips unchanged.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::Color;
+//! use led::color::PERCENT_ERROR;
+//!
+//! # fn main() {
+//! let c = Color::new(0.123, 0.456, 0.789);
+//! let q = c.quantized();
+//! assert!(q.approx_eq(&c, PERCENT_ERROR));
+//! assert_eq!(q.quantized(), q);
+//! assert_eq!(q.to_percent()[1].get(), 46);
+//! # }
+//! ```
+
+use super::{Color, BGRA, clampf};
+use std::os::raw::c_int;
+
+/// Largest error per channel of a conversion to percent.
+pub const PERCENT_ERROR: f32 = 0.5 / 100.0;
+
+/// Largest error per channel of a conversion to a bitmap byte.
+pub const BYTE_ERROR: f32 = 0.5 / 255.0;
+
+/// A channel in whole percent, `0..=100`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Percent(u8);
+
+impl Percent {
+    pub const ZERO: Percent = Percent(0);
+    pub const FULL: Percent = Percent(100);
+
+    /// `None` if `p` is above 100.
+    pub fn new(p: u8) -> Option<Percent> {
+        match p <= 100 {
+            true => Some(Percent(p)),
+            false => None,
+        }
+    }
+
+    /// Nearest percent to `v` in [0,1], clamped.
+    pub fn from_f32(v: f32) -> Percent {
+        Percent((clampf(v) * 100.0).round() as u8)
+    }
+
+    /// `p` clamped to `0..=100`.
+    pub fn from_c_int(p: c_int) -> Percent {
+        Percent(p.clamp(0, 100) as u8)
+    }
+
+    pub fn get(self) -> u8 {
+        self.0
+    }
+
+    /// The percent as a fraction in [0,1], exact for every percent.
+    pub fn to_f32(self) -> f32 {
+        self.0 as f32 / 100.0
+    }
+}
+
+impl From<Percent> for c_int {
+    fn from(p: Percent) -> c_int {
+        p.0 as c_int
+    }
+}
+
+/// Nearest byte to `v` in [0,1], clamped.
+pub(crate) fn f2u8(v: f32) -> u8 {
+    (clampf(v) * 255.0).round() as u8
+}
+
+impl Color {
+    /// The nearest whole percentages, as sent to the SDK.
+    pub fn to_percent(&self) -> [Percent; 3] {
+        [Percent::from_f32(self.r), Percent::from_f32(self.g), Percent::from_f32(self.b)]
+    }
+
+    pub fn from_percent(p: [Percent; 3]) -> Color {
+        Color { r: p[0].to_f32(), g: p[1].to_f32(), b: p[2].to_f32() }
+    }
+
+    /// The color as the SDK shows it, rounded to whole percentages.
+    pub fn quantized(&self) -> Color {
+        Color::from_percent(self.to_percent())
+    }
+
+    /// The color as a bitmap shows it, rounded to bytes.
+    pub fn quantized_bytes(&self) -> Color {
+        Color::from(BGRA::from(*self))
+    }
+}
@@ -0,0 +1,226 @@
+//! Conversions between sRGB and other color spaces.
+//!
+//! [`Color`](../struct.Color.html) is gamma encoded sRGB, as sent to the SDK.
+//! Mixing colors in [`LinearRgb`](struct.LinearRgb.html) is physically correct,
+//! and mixing in [`Oklab`](struct.Oklab.html) gives perceptually even steps.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::Color;
+//!
+//! # fn main() {
+//! let c = Color::new(0.8, 0.3, 0.1);
+//! assert!(Color::from_oklab(c.to_oklab()).approx_eq(&c, 1e-4));
+//! assert!(Color::from_hsl(120.0, 1.0, 0.25).approx_eq(&Color::new(0.0, 0.5, 0.0), 1e-6));
+//!
+//! // Halfway from red to green in Oklab is a warm yellow, not the dark olive of an RGB lerp.
+//! let mid = Color::RED.lerp_oklab(Color::LIME, 0.5);
+//! assert!(mid.r > 0.6 && mid.g > 0.5);
+//! # }
+//! ```
+
+use super::{Color, hsl2rgb, clampf};
+
+/// sRGB transfer function, gamma encoded to linear light.
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 }
+    else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// Inverse sRGB transfer function, linear light to gamma encoded.
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 { c * 12.92 }
+    else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// sRGB primaries with linear light components.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LinearRgb {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+/// CIE 1931 XYZ, relative to the D65 white point with `Y = 1` for white.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Xyz {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Oklab perceptual color space, lightness `l` in [0,1].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// Oklab in polar form, hue `h` in degrees.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl From<Color> for LinearRgb {
+    fn from(c: Color) -> LinearRgb {
+        let c = c.clamp();
+        LinearRgb { r: srgb_to_linear(c.r), g: srgb_to_linear(c.g), b: srgb_to_linear(c.b) }
+    }
+}
+
+/// Out of gamut components are clamped.
+impl From<LinearRgb> for Color {
+    fn from(c: LinearRgb) -> Color {
+        Color::new(linear_to_srgb(clampf(c.r)), linear_to_srgb(clampf(c.g)), linear_to_srgb(clampf(c.b)))
+    }
+}
+
+impl From<LinearRgb> for Xyz {
+    fn from(c: LinearRgb) -> Xyz {
+        Xyz {
+            x: 0.412_456_4 * c.r + 0.357_576_1 * c.g + 0.180_437_5 * c.b,
+            y: 0.212_672_9 * c.r + 0.715_152_2 * c.g + 0.072_175_0 * c.b,
+            z: 0.019_333_9 * c.r + 0.119_192 * c.g + 0.950_304_1 * c.b,
+        }
+    }
+}
+
+impl From<Xyz> for LinearRgb {
+    fn from(c: Xyz) -> LinearRgb {
+        LinearRgb {
+            r: 3.240_454_2 * c.x - 1.537_138_5 * c.y - 0.498_531_4 * c.z,
+            g: -0.969_266 * c.x + 1.876_010_8 * c.y + 0.041_556_0 * c.z,
+            b: 0.055_643_4 * c.x - 0.204_025_9 * c.y + 1.057_225_2 * c.z,
+        }
+    }
+}
+
+impl From<LinearRgb> for Oklab {
+    fn from(c: LinearRgb) -> Oklab {
+        let l = 0.412_221_47 * c.r + 0.536_332_54 * c.g + 0.051_445_995 * c.b;
+        let m = 0.211_903_5 * c.r + 0.680_699_5 * c.g + 0.107_396_96 * c.b;
+        let s = 0.088_302_46 * c.r + 0.281_718_85 * c.g + 0.629_978_7 * c.b;
+
+        let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+}
+
+impl From<Oklab> for LinearRgb {
+    fn from(c: Oklab) -> LinearRgb {
+        let l = c.l + 0.396_337_78 * c.a + 0.215_803_76 * c.b;
+        let m = c.l - 0.105_561_346 * c.a - 0.063_854_17 * c.b;
+        let s = c.l - 0.089_484_18 * c.a - 1.291_485_5 * c.b;
+
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        LinearRgb {
+            r: 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            g: -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            b: -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        }
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(c: Oklab) -> Oklch {
+        let h = c.b.atan2(c.a).to_degrees();
+        Oklch { l: c.l, c: c.a.hypot(c.b), h: if h < 0.0 { h + 360.0 } else { h } }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(c: Oklch) -> Oklab {
+        let h = c.h.to_radians();
+        Oklab { l: c.l, a: c.c * h.cos(), b: c.c * h.sin() }
+    }
+}
+
+impl Color {
+    /// Hue in degrees [0,360), saturation and value in [0,1].
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let c = self.clamp();
+        let max = c.r.max(c.g).max(c.b);
+        let min = c.r.min(c.g).min(c.b);
+        let delta = max - min;
+
+        let h = if delta <= 0.0 {
+            0.0
+        } else if max == c.r {
+            60.0 * ((c.g - c.b) / delta)
+        } else if max == c.g {
+            60.0 * ((c.b - c.r) / delta + 2.0)
+        } else {
+            60.0 * ((c.r - c.g) / delta + 4.0)
+        };
+        let h = if h < 0.0 { h + 360.0 } else { h };
+        let s = if max <= 0.0 { 0.0 } else { delta / max };
+        (h, s, max)
+    }
+
+    /// Hue in degrees, saturation and lightness in [0,1].
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Color {
+        hsl2rgb(h, s, l).into()
+    }
+
+    /// Hue in degrees [0,360), saturation and lightness in [0,1].
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (h, sv, v) = self.to_hsv();
+        let l = v * (1.0 - sv / 2.0);
+        let s = if l <= 0.0 || l >= 1.0 { 0.0 } else { (v - l) / l.min(1.0 - l) };
+        (h, s, l)
+    }
+
+    pub fn to_linear(&self) -> LinearRgb {
+        (*self).into()
+    }
+
+    pub fn from_linear(c: LinearRgb) -> Color {
+        c.into()
+    }
+
+    pub fn to_xyz(&self) -> Xyz {
+        self.to_linear().into()
+    }
+
+    /// Out of gamut colors are clamped.
+    pub fn from_xyz(c: Xyz) -> Color {
+        LinearRgb::from(c).into()
+    }
+
+    pub fn to_oklab(&self) -> Oklab {
+        self.to_linear().into()
+    }
+
+    /// Out of gamut colors are clamped.
+    pub fn from_oklab(c: Oklab) -> Color {
+        LinearRgb::from(c).into()
+    }
+
+    pub fn to_oklch(&self) -> Oklch {
+        self.to_oklab().into()
+    }
+
+    /// Out of gamut colors are clamped.
+    pub fn from_oklch(c: Oklch) -> Color {
+        Color::from_oklab(c.into())
+    }
+
+    /// Like [`lerp`](#method.lerp), but interpolated in Oklab for perceptually even steps.
+    pub fn lerp_oklab(&self, other: Color, t: f32) -> Color {
+        let t = clampf(t);
+        let (a, b) = (self.to_oklab(), other.to_oklab());
+        Color::from_oklab(Oklab {
+            l: a.l + (b.l - a.l) * t,
+            a: a.a + (b.a - a.a) * t,
+            b: a.b + (b.b - a.b) * t,
+        })
+    }
+}
@@ -0,0 +1,187 @@
+//! Color temperature, white points and white balance.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::Color;
+//! use led::color::{WhitePoint, WhiteBalance};
+//!
+//! # fn main() {
+//! // Warm white for ambient lighting.
+//! let warm = Color::from_kelvin(2700.0);
+//! assert!(warm.r > 0.99 && warm.b < 0.5);
+//!
+//! // Night mode: move every color halfway to the white of a 3000 K lamp.
+//! let night = Color::new(0.3, 0.5, 1.0).shift_toward(WhitePoint::from_kelvin(3000.0), 0.5);
+//! assert!(night.b < 1.0);
+//!
+//! // LEDs whose white looks like 9000 K get their blue turned down.
+//! let wb = WhiteBalance::from_kelvin(9000.0);
+//! assert!(wb.apply(Color::WHITE).b < 1.0);
+//! # }
+//! ```
+
+use super::{Color, LinearRgb, Xyz, clampf};
+
+/// Lowest temperature of the blackbody approximation, in kelvin.
+pub const MIN_KELVIN: f32 = 1667.0;
+/// Highest temperature of the blackbody approximation, in kelvin.
+pub const MAX_KELVIN: f32 = 25000.0;
+
+/// CIE xy chromaticity of a blackbody at `kelvin`, clamped to
+/// [`MIN_KELVIN`](constant.MIN_KELVIN.html)..[`MAX_KELVIN`](constant.MAX_KELVIN.html).
+///
+/// Uses the cubic spline approximation of the Planckian locus by Kim et al.
+pub fn kelvin_to_xy(kelvin: f32) -> (f32, f32) {
+    let t = kelvin.clamp(MIN_KELVIN, MAX_KELVIN) as f64;
+    let (t2, t3) = (t * t, t * t * t);
+
+    let x = if t <= 4000.0 {
+        -0.266_123_9e9 / t3 - 0.234_358_9e6 / t2 + 0.877_695_6e3 / t + 0.179_910
+    } else {
+        -3.025_846_9e9 / t3 + 2.107_037_9e6 / t2 + 0.222_634_7e3 / t + 0.240_390
+    };
+    let (x2, x3) = (x * x, x * x * x);
+    let y = if t <= 2222.0 {
+        -1.106_381_4 * x3 - 1.348_110_20 * x2 + 2.185_558_32 * x - 0.202_196_83
+    } else if t <= 4000.0 {
+        -0.954_947_6 * x3 - 1.374_185_93 * x2 + 2.091_370_15 * x - 0.167_488_67
+    } else {
+        3.081_758_0 * x3 - 5.873_386_70 * x2 + 3.751_129_97 * x - 0.370_014_83
+    };
+    (x as f32, y as f32)
+}
+
+/// A reference white, as CIE xy chromaticity.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WhitePoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl WhitePoint {
+    /// Daylight, the white point of sRGB and of [`Color::WHITE`](../struct.Color.html#associatedconstant.WHITE).
+    pub const D65: WhitePoint = WhitePoint { x: 0.312_71, y: 0.329_02 };
+    /// Horizon light, used for print.
+    pub const D50: WhitePoint = WhitePoint { x: 0.345_67, y: 0.358_50 };
+    /// Incandescent light.
+    pub const A: WhitePoint = WhitePoint { x: 0.447_57, y: 0.407_45 };
+
+    /// White of a blackbody at `kelvin`.
+    pub fn from_kelvin(kelvin: f32) -> WhitePoint {
+        let (x, y) = kelvin_to_xy(kelvin);
+        WhitePoint { x, y }
+    }
+
+    /// The white point in XYZ with `Y = 1`.
+    pub fn to_xyz(&self) -> Xyz {
+        Xyz { x: self.x / self.y, y: 1.0, z: (1.0 - self.x - self.y) / self.y }
+    }
+
+    /// Interpolate between two white points in xy.
+    pub fn lerp(&self, other: WhitePoint, t: f32) -> WhitePoint {
+        let t = clampf(t);
+        WhitePoint { x: self.x + (other.x - self.x) * t, y: self.y + (other.y - self.y) * t }
+    }
+}
+
+const BRADFORD: [[f32; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+const BRADFORD_INV: [[f32; 3]; 3] = [
+    [0.986_992_9, -0.147_054_3, 0.159_962_7],
+    [0.432_305_3, 0.518_360_3, 0.049_291_2],
+    [-0.008_528_7, 0.040_042_8, 0.968_486_7],
+];
+
+fn mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+/// Bradford chromatic adaptation of `c` seen under `from` to how it looks under `to`.
+pub fn adapt_xyz(c: Xyz, from: WhitePoint, to: WhitePoint) -> Xyz {
+    let (f, t) = (from.to_xyz(), to.to_xyz());
+    let src = mul(&BRADFORD, [f.x, f.y, f.z]);
+    let dst = mul(&BRADFORD, [t.x, t.y, t.z]);
+
+    let cone = mul(&BRADFORD, [c.x, c.y, c.z]);
+    let scaled = [cone[0] * dst[0] / src[0], cone[1] * dst[1] / src[1], cone[2] * dst[2] / src[2]];
+    let v = mul(&BRADFORD_INV, scaled);
+    Xyz { x: v[0], y: v[1], z: v[2] }
+}
+
+/// `c` with every channel of linear light scaled so the brightest is 1.
+fn normalized(c: LinearRgb) -> Color {
+    let c = LinearRgb { r: c.r.max(0.0), g: c.g.max(0.0), b: c.b.max(0.0) };
+    let max = c.r.max(c.g).max(c.b);
+    if max <= 0.0 {
+        return Color::BLACK;
+    }
+    Color::from_linear(LinearRgb { r: c.r / max, g: c.g / max, b: c.b / max })
+}
+
+impl Color {
+    /// Brightest color of a blackbody at `kelvin`, 6500 K is close to white.
+    pub fn from_kelvin(kelvin: f32) -> Color {
+        normalized(WhitePoint::from_kelvin(kelvin).to_xyz().into())
+    }
+
+    /// Adapt the color from the sRGB white point to `white`.
+    ///
+    /// `amount` in [0,1] moves the white point only part of the way, for gradual night modes.
+    pub fn shift_toward(&self, white: WhitePoint, amount: f32) -> Color {
+        let to = WhitePoint::D65.lerp(white, amount);
+        Color::from_xyz(adapt_xyz(self.to_xyz(), WhitePoint::D65, to))
+    }
+}
+
+/// Per-channel gains in linear light, correcting the white of a device.
+///
+/// Set on a [`Driver`](../../struct.Driver.html#method.set_white_balance) it is
+/// applied to every color before conversion to the percent the SDK takes.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WhiteBalance {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl Default for WhiteBalance {
+    fn default() -> WhiteBalance {
+        WhiteBalance::new(1.0, 1.0, 1.0)
+    }
+}
+
+impl WhiteBalance {
+    /// Gains are clamped to [0,1], LEDs can't go brighter than full.
+    pub fn new(r: f32, g: f32, b: f32) -> WhiteBalance {
+        WhiteBalance { r: clampf(r), g: clampf(g), b: clampf(b) }
+    }
+
+    /// Correction for a device that shows `white` when sent [`Color::WHITE`](../struct.Color.html#associatedconstant.WHITE).
+    ///
+    /// Channels missing from `white` can't be corrected and keep full gain.
+    pub fn from_white(white: Color) -> WhiteBalance {
+        let l = white.to_linear();
+        let min = [l.r, l.g, l.b].iter().cloned().filter(|&v| v > 0.0).fold(1.0f32, f32::min);
+        let gain = |v: f32| if v > 0.0 { min / v } else { 1.0 };
+        WhiteBalance::new(gain(l.r), gain(l.g), gain(l.b))
+    }
+
+    /// Correction for a device whose white looks like a blackbody at `kelvin`.
+    pub fn from_kelvin(kelvin: f32) -> WhiteBalance {
+        WhiteBalance::from_white(Color::from_kelvin(kelvin))
+    }
+
+    /// Apply the gains to `color`.
+    pub fn apply(&self, color: Color) -> Color {
+        let l = color.to_linear();
+        Color::from_linear(LinearRgb { r: l.r * self.r, g: l.g * self.g, b: l.b * self.b })
+    }
+}
@@ -0,0 +1,330 @@
+//! Stacking independent layers of lighting.
+//!
+//! A [`Compositor`](struct.Compositor.html) holds layers from bottom to top,
+//! for example a base theme, a reactive typing layer and a notification
+//! overlay. Each layer only knows its own content; the compositor blends the
+//! visible ones into one [`FloatFrame`](../struct.FloatFrame.html), and only
+//! does so again when a layer has changed.
+//!
+//! Layers are blended on the gamma encoded colors, like
+//! [`Bitmap::overlay`](../struct.Bitmap.html#method.overlay).
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::{Bitmap, BlendMode, Color, FloatFrame, Key, KeySet};
+//! use led::compositor::{Compositor, Layer};
+//!
+//! # fn main() {
+//! let mut compositor = Compositor::new();
+//! compositor.push(Layer::bitmap(Bitmap::filled(Color::BLUE)));
+//!
+//! let mut typing = Layer::bitmap(Bitmap::transparent());
+//! typing.set_mode(BlendMode::Screen);
+//! let typing = compositor.push(typing);
+//!
+//! let mut alert = Layer::frame(FloatFrame::filled(Color::RED));
+//! alert.set_mask(Some(KeySet::g_keys()));
+//! alert.set_visible(false);
+//! let alert = compositor.push(alert);
+//!
+//! compositor.layer_mut(typing).unwrap().bitmap_mut().unwrap().set_key(Key::A, Color::LIME);
+//! assert_eq!(compositor.flatten().get(Key::A), Color::CYAN);
+//!
+//! compositor.layer_mut(alert).unwrap().set_visible(true);
+//! assert_eq!(compositor.flatten().get(Key::G_1), Color::RED);
+//! # }
+//! ```
+
+use bitmap::Bitmap;
+use color::{BlendMode, Color, ColorA};
+use frame::FloatFrame;
+use keys::{KeySet, bitmap_position};
+use sys::Key;
+use {Driver, Error};
+
+/// What a layer shows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Content {
+    /// Cells with alpha, keys outside the bitmap are transparent.
+    Bitmap(Box<Bitmap>),
+    /// Opaque color on every key.
+    Frame(Box<FloatFrame>),
+}
+
+/// One layer of a [`Compositor`](struct.Compositor.html).
+#[derive(Debug, Clone)]
+pub struct Layer {
+    content: Content,
+    opacity: f32,
+    mode: BlendMode,
+    mask: Option<KeySet>,
+    visible: bool,
+    dirty: bool,
+}
+
+impl Layer {
+    /// Visible, fully opaque layer with normal blending and no mask.
+    pub fn new(content: Content) -> Layer {
+        Layer {
+            content,
+            opacity: 1.0,
+            mode: BlendMode::Normal,
+            mask: None,
+            visible: true,
+            dirty: true,
+        }
+    }
+
+    pub fn bitmap(bitmap: Bitmap) -> Layer {
+        Layer::new(Content::Bitmap(Box::new(bitmap)))
+    }
+
+    pub fn frame(frame: FloatFrame) -> Layer {
+        Layer::new(Content::Frame(Box::new(frame)))
+    }
+
+    pub fn content(&self) -> &Content {
+        &self.content
+    }
+
+    pub fn set_content(&mut self, content: Content) {
+        self.content = content;
+        self.content_changed();
+    }
+
+    /// The bitmap to draw on, `None` if the layer shows a frame.
+    pub fn bitmap_mut(&mut self) -> Option<&mut Bitmap> {
+        if let Content::Bitmap(_) = self.content {
+            self.content_changed();
+        }
+        match self.content {
+            Content::Bitmap(ref mut b) => Some(&mut **b),
+            Content::Frame(_) => None,
+        }
+    }
+
+    /// The frame to draw on, `None` if the layer shows a bitmap.
+    pub fn frame_mut(&mut self) -> Option<&mut FloatFrame> {
+        if let Content::Frame(_) = self.content {
+            self.content_changed();
+        }
+        match self.content {
+            Content::Frame(ref mut f) => Some(&mut **f),
+            Content::Bitmap(_) => None,
+        }
+    }
+
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Opacity in [0,1], multiplied with the alpha of the content.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self.content_changed();
+    }
+
+    pub fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    /// How the layer is blended onto the layers below.
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.mode = mode;
+        self.content_changed();
+    }
+
+    pub fn mask(&self) -> Option<KeySet> {
+        self.mask
+    }
+
+    /// Only draw the keys in `mask`, or every key with `None`.
+    pub fn set_mask(&mut self, mask: Option<KeySet>) {
+        self.mask = mask;
+        self.content_changed();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        if visible != self.visible {
+            self.visible = visible;
+            self.dirty = true;
+        }
+    }
+
+    /// Returns `true` if the layer changed since the compositor last flattened it.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Hidden layers don't change the output.
+    fn content_changed(&mut self) {
+        self.dirty |= self.visible;
+    }
+
+    /// Color and coverage of `key`, opacity and mask applied.
+    fn sample(&self, key: Key) -> ColorA {
+        if self.mask.is_some_and(|m| !m.contains(key)) {
+            return ColorA::TRANSPARENT;
+        }
+        let c = match self.content {
+            Content::Bitmap(ref b) => match bitmap_position(key) {
+                Some((x, y)) => b.get_rgba(x, y),
+                None => ColorA::TRANSPARENT,
+            },
+            Content::Frame(ref f) => f.get(key).with_alpha(1.0),
+        };
+        ColorA { a: c.a * self.opacity, ..c }
+    }
+}
+
+/// Identifies a layer of a [`Compositor`](struct.Compositor.html), stays valid while layers are added and removed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LayerId(usize);
+
+/// Ordered layers, flattened into one frame, see the [module docs](index.html).
+#[derive(Debug, Clone)]
+pub struct Compositor {
+    layers: Vec<(LayerId, Layer)>,
+    next_id: usize,
+    /// Layers were added, removed or reordered.
+    restacked: bool,
+    output: FloatFrame,
+    /// `output` changed since it was last sent.
+    unsent: bool,
+}
+
+impl Default for Compositor {
+    fn default() -> Compositor {
+        Compositor::new()
+    }
+}
+
+impl Compositor {
+    /// Compositor without layers, it flattens to black.
+    pub fn new() -> Compositor {
+        Compositor {
+            layers: Vec::new(),
+            next_id: 0,
+            restacked: true,
+            output: FloatFrame::new(),
+            unsent: true,
+        }
+    }
+
+    /// Add `layer` on top.
+    pub fn push(&mut self, layer: Layer) -> LayerId {
+        let index = self.layers.len();
+        self.insert(index, layer)
+    }
+
+    /// Add `layer` at `index`, 0 is the bottom.
+    ///
+    /// # Panics
+    /// If `index` is greater than the number of layers.
+    pub fn insert(&mut self, index: usize, layer: Layer) -> LayerId {
+        let id = LayerId(self.next_id);
+        self.next_id += 1;
+        self.layers.insert(index, (id, layer));
+        self.restacked = true;
+        id
+    }
+
+    pub fn remove(&mut self, id: LayerId) -> Option<Layer> {
+        let index = self.index(id)?;
+        self.restacked = true;
+        Some(self.layers.remove(index).1)
+    }
+
+    /// Move a layer to `index`, 0 is the bottom. Returns `false` if there is no such layer.
+    ///
+    /// # Panics
+    /// If `index` is not less than the number of layers.
+    pub fn move_to(&mut self, id: LayerId, index: usize) -> bool {
+        match self.index(id) {
+            Some(from) => {
+                let layer = self.layers.remove(from);
+                self.layers.insert(index, layer);
+                self.restacked = true;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Position of a layer, 0 is the bottom.
+    pub fn index(&self, id: LayerId) -> Option<usize> {
+        self.layers.iter().position(|&(i, _)| i == id)
+    }
+
+    pub fn layer(&self, id: LayerId) -> Option<&Layer> {
+        self.layers.iter().find(|&&(i, _)| i == id).map(|(_, l)| l)
+    }
+
+    /// The layer to change, changes mark it dirty.
+    pub fn layer_mut(&mut self, id: LayerId) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|&&mut (i, _)| i == id).map(|(_, l)| l)
+    }
+
+    /// The layers from bottom to top.
+    pub fn layers(&self) -> impl Iterator<Item = (LayerId, &Layer)> {
+        self.layers.iter().map(|&(id, ref l)| (id, l))
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns `true` if the next [`flatten`](#method.flatten) has to render.
+    pub fn is_dirty(&self) -> bool {
+        self.restacked || self.layers.iter().any(|(_, l)| l.dirty)
+    }
+
+    /// The visible layers blended onto black, rendered again only if a layer is dirty.
+    pub fn flatten(&mut self) -> &FloatFrame {
+        if self.is_dirty() {
+            self.render();
+        }
+        &self.output
+    }
+
+    fn render(&mut self) {
+        let visible: Vec<&Layer> = self.layers.iter().map(|(_, l)| l).filter(|l| l.visible).collect();
+        self.output.paint(|key| {
+            visible.iter().fold(Color::BLACK.with_alpha(1.0), |base, l| {
+                let top = l.sample(key);
+                if top.a > 0.0 { top.over(base, l.mode) } else { base }
+            }).color()
+        });
+
+        for &mut (_, ref mut l) in &mut self.layers {
+            l.dirty = false;
+        }
+        self.restacked = false;
+        self.unsent = true;
+    }
+
+    /// The flattened keys of the bitmap, for [`Driver::set_lighting_from_bitmap`](../struct.Driver.html#method.set_lighting_from_bitmap).
+    pub fn to_bitmap(&mut self) -> Bitmap {
+        self.flatten().to_bitmap()
+    }
+
+    /// Send the flattened frame with [`Driver::set_lighting_from_frame`](../struct.Driver.html#method.set_lighting_from_frame),
+    /// if it changed since it was last sent. Returns `true` if it was sent.
+    pub fn send(&mut self, driver: &mut Driver) -> Result<bool, Error> {
+        self.flatten();
+        if !self.unsent {
+            return Ok(false);
+        }
+        driver.set_lighting_from_frame(&self.output)?;
+        self.unsent = false;
+        Ok(true)
+    }
+}
@@ -0,0 +1,280 @@
+//! Capabilities of lighting-capable Logitech devices.
+//!
+//! Encodes [DEVICES.md](https://github.com/henninglive/logitech-led/blob/master/DEVICES.md),
+//! so applications can tell which effects a device can render.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! # fn main() {
+//! use led::DeviceType;
+//! use led::devices::{self, DeviceModel};
+//!
+//! let per_key = devices::models_supporting(DeviceType::PERKEY_RGB);
+//! assert!(per_key.contains(&DeviceModel::G910));
+//! assert!(!per_key.contains(&DeviceModel::G710Plus));
+//! # }
+//! ```
+
+use color::Color;
+use keys::KeySet;
+use zone::{Zone, ZoneTarget};
+use sys::{DeviceType, Key};
+use std::fmt;
+
+/// Lighting-capable device supported by the SDK.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeviceModel {
+    G910,
+    G810,
+    G610,
+    G710Plus,
+    G633,
+    G933,
+    G600,
+    G510,
+    G110,
+    G19,
+    G105,
+    G105CallOfDuty,
+    G300,
+    G900,
+    G303,
+    G11,
+    G13,
+    G15v1,
+    G15v2,
+}
+
+/// Colors a device can show.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColorDepth {
+    /// Red, green and blue.
+    Rgb,
+    /// Red and blue, green is ignored.
+    RedBlue,
+    /// A single color, only the brightness can be set.
+    Monochrome,
+}
+
+/// How a device turns the requested color into brightness.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Brightness {
+    /// Every channel is shown at the requested level.
+    PerChannel,
+    /// Every channel is on above 50% and off below.
+    ChannelOnOff,
+    /// The highest of R, G and B defines the brightness, at full resolution.
+    MaxChannel,
+    /// The highest of R, G and B defines the brightness, in 3 levels:
+    /// off below 33%, low up to 66% and high above.
+    MaxChannelLevels,
+}
+
+/// Lighting capabilities of a [`DeviceModel`](enum.DeviceModel.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// SDK target device type that reaches the device.
+    pub device_type: DeviceType,
+    pub color_depth: ColorDepth,
+    /// Accepts the per-key and bitmap functions.
+    pub per_key: bool,
+    /// Keys that can be lit individually, empty unless `per_key`.
+    pub keys: KeySet,
+    /// Device type used to address the zones.
+    pub zone_target: Option<ZoneTarget>,
+    /// Number of zones that can be set with
+    /// [`Driver::set_lighting_for_zone`](../struct.Driver.html#method.set_lighting_for_zone),
+    /// `None` if unknown. DEVICES.md doesn't list zone counts, so it is `None` for every
+    /// model with a `zone_target` and 0 for the others.
+    pub zones: Option<u8>,
+    pub brightness: Brightness,
+    /// Only works with the SDK when set to host mode in Logitech Gaming Software.
+    pub requires_host_mode: bool,
+}
+
+impl Capabilities {
+    fn rgb() -> Capabilities {
+        Capabilities {
+            device_type: DeviceType::RGB,
+            color_depth: ColorDepth::Rgb,
+            per_key: false,
+            keys: KeySet::empty(),
+            zone_target: None,
+            zones: Some(0),
+            brightness: Brightness::PerChannel,
+            requires_host_mode: false,
+        }
+    }
+
+    fn monochrome(brightness: Brightness) -> Capabilities {
+        Capabilities {
+            device_type: DeviceType::MONOCHROME,
+            color_depth: ColorDepth::Monochrome,
+            brightness,
+            ..Capabilities::rgb()
+        }
+    }
+
+    fn per_key(color_depth: ColorDepth, brightness: Brightness, keys: KeySet) -> Capabilities {
+        Capabilities {
+            device_type: DeviceType::PERKEY_RGB,
+            color_depth,
+            per_key: true,
+            keys,
+            brightness,
+            ..Capabilities::rgb()
+        }
+    }
+
+    fn zoned(target: ZoneTarget) -> Capabilities {
+        Capabilities {
+            zone_target: Some(target),
+            zones: None,
+            ..Capabilities::rgb()
+        }
+    }
+
+    /// The zones of the device, `None` if it has zones but their number is unknown.
+    pub fn zone_list(&self) -> Option<Vec<Zone>> {
+        match (self.zone_target, self.zones) {
+            (Some(target), Some(zones)) => Some((0..zones).map(|i| Zone::new(target, i)).collect()),
+            (None, _) => Some(Vec::new()),
+            (Some(_), None) => None,
+        }
+    }
+
+    /// Returns `true` if `key` can be lit individually.
+    pub fn has_key(&self, key: Key) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Approximate the color the device shows when `color` is requested.
+    ///
+    /// Monochrome devices are simulated as white LEDs.
+    pub fn simulate(&self, color: Color) -> Color {
+        let c = color.clamp();
+        let c = match self.color_depth {
+            ColorDepth::RedBlue => Color { g: 0.0, ..c },
+            _ => c,
+        };
+
+        let level = |v: f32| -> f32 {
+            let p = v * 100.0;
+            if p < 33.0 { 0.0 } else if p <= 66.0 { 0.5 } else { 1.0 }
+        };
+        let on_off = |v: f32| -> f32 { if v * 100.0 > 50.0 { 1.0 } else { 0.0 } };
+        let max = c.r.max(c.g).max(c.b);
+
+        match self.brightness {
+            Brightness::PerChannel => c,
+            Brightness::ChannelOnOff => Color::new(on_off(c.r), on_off(c.g), on_off(c.b)),
+            Brightness::MaxChannel => Color::new(max, max, max),
+            Brightness::MaxChannelLevels => {
+                let l = level(max);
+                Color::new(l, l, l)
+            },
+        }
+    }
+}
+
+impl DeviceModel {
+    /// Every model, in the order of DEVICES.md.
+    pub const ALL: [DeviceModel; 19] = [
+        DeviceModel::G910, DeviceModel::G810, DeviceModel::G610, DeviceModel::G710Plus,
+        DeviceModel::G633, DeviceModel::G933, DeviceModel::G600, DeviceModel::G510,
+        DeviceModel::G110, DeviceModel::G19, DeviceModel::G105, DeviceModel::G105CallOfDuty,
+        DeviceModel::G300, DeviceModel::G900, DeviceModel::G303, DeviceModel::G11,
+        DeviceModel::G13, DeviceModel::G15v1, DeviceModel::G15v2,
+    ];
+
+    /// Product name.
+    pub fn name(self) -> &'static str {
+        match self {
+            DeviceModel::G910 => "G910 Orion Spark",
+            DeviceModel::G810 => "G810 Orion Spectrum",
+            DeviceModel::G610 => "G610 Orion Brown",
+            DeviceModel::G710Plus => "G710+",
+            DeviceModel::G633 => "G633",
+            DeviceModel::G933 => "G933",
+            DeviceModel::G600 => "G600",
+            DeviceModel::G510 => "G510/G510s",
+            DeviceModel::G110 => "G110",
+            DeviceModel::G19 => "G19/G19s",
+            DeviceModel::G105 => "G105",
+            DeviceModel::G105CallOfDuty => "G105 Call Of Duty",
+            DeviceModel::G300 => "G300",
+            DeviceModel::G900 => "G900",
+            DeviceModel::G303 => "G303 Daedalus Apex",
+            DeviceModel::G11 => "G11",
+            DeviceModel::G13 => "G13",
+            DeviceModel::G15v1 => "G15 v1",
+            DeviceModel::G15v2 => "G15 v2",
+        }
+    }
+
+    pub fn capabilities(self) -> Capabilities {
+        match self {
+            DeviceModel::G910 =>
+                Capabilities::per_key(ColorDepth::Rgb, Brightness::PerChannel, KeySet::all()),
+            DeviceModel::G810 =>
+                Capabilities::per_key(ColorDepth::Rgb, Brightness::PerChannel,
+                    KeySet::bitmap().union(KeySet::logo())),
+            // Accepts the PERKEY_RGB functions, but only shows the highest of R, G and B.
+            DeviceModel::G610 =>
+                Capabilities::per_key(ColorDepth::Monochrome, Brightness::MaxChannel, KeySet::bitmap()),
+            DeviceModel::G710Plus |
+            DeviceModel::G105 |
+            DeviceModel::G105CallOfDuty =>
+                Capabilities::monochrome(Brightness::MaxChannel),
+            DeviceModel::G11 |
+            DeviceModel::G15v1 |
+            DeviceModel::G15v2 =>
+                Capabilities::monochrome(Brightness::MaxChannelLevels),
+            DeviceModel::G633 |
+            DeviceModel::G933 => Capabilities::zoned(ZoneTarget::Headset),
+            DeviceModel::G900 => Capabilities::zoned(ZoneTarget::Mouse),
+            DeviceModel::G600 => Capabilities {
+                requires_host_mode: true,
+                ..Capabilities::rgb()
+            },
+            DeviceModel::G110 => Capabilities {
+                color_depth: ColorDepth::RedBlue,
+                ..Capabilities::rgb()
+            },
+            DeviceModel::G300 => Capabilities {
+                brightness: Brightness::ChannelOnOff,
+                ..Capabilities::rgb()
+            },
+            DeviceModel::G510 |
+            DeviceModel::G19 |
+            DeviceModel::G303 |
+            DeviceModel::G13 => Capabilities::rgb(),
+        }
+    }
+
+    /// Returns `true` if the device is reached by SDK calls targeting `device_type`.
+    pub fn supports(self, device_type: DeviceType) -> bool {
+        device_type.intersects(self.capabilities().device_type)
+    }
+}
+
+impl fmt::Display for DeviceModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Models reached by SDK calls targeting `device_type`.
+pub fn models_supporting(device_type: DeviceType) -> Vec<DeviceModel> {
+    DeviceModel::ALL.iter().cloned().filter(|m| m.supports(device_type)).collect()
+}
+
+/// Models whose keys can be lit individually.
+pub fn per_key_models() -> Vec<DeviceModel> {
+    DeviceModel::ALL.iter().cloned().filter(|m| m.capabilities().per_key).collect()
+}
+
+/// Models whose lighting is addressed by zone.
+pub fn zoned_models() -> Vec<DeviceModel> {
+    DeviceModel::ALL.iter().cloned().filter(|m| m.capabilities().zone_target.is_some()).collect()
+}
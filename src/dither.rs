@@ -0,0 +1,75 @@
+//! Temporal dithering of the percent quantization.
+//!
+//! The SDK takes whole percentages, so slow fades step visibly, especially at
+//! low brightness. With [`DitherMode::Temporal`](enum.DitherMode.html) the
+//! quantization error of each output is carried over to its next frame, so
+//! over time the sent percentages average out to the requested color.
+
+use backend::Percent3;
+use color::Color;
+use std::collections::BTreeMap;
+use std::os::raw::c_int;
+
+/// How colors are quantized to percent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum DitherMode {
+    /// Every frame is rounded to the nearest percent on its own.
+    #[default]
+    Off,
+    /// The quantization error is diffused across frames, per output.
+    Temporal,
+}
+
+/// Quantizer that keeps the error of every output `K` between frames.
+#[derive(Debug, Clone, Default)]
+pub struct Dither<K: Ord> {
+    mode: DitherMode,
+    error: BTreeMap<K, [f32; 3]>,
+}
+
+impl<K: Ord> Dither<K> {
+    pub fn new(mode: DitherMode) -> Dither<K> {
+        Dither { mode, error: BTreeMap::new() }
+    }
+
+    pub fn mode(&self) -> DitherMode {
+        self.mode
+    }
+
+    /// Change the mode, forgetting the carried error.
+    pub fn set_mode(&mut self, mode: DitherMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    /// Forget the carried error of every output.
+    pub fn reset(&mut self) {
+        self.error.clear();
+    }
+
+    /// Quantize `color` for `output` to percent.
+    pub fn quantize(&mut self, output: K, color: Color) -> Percent3 {
+        let c = color.clamp();
+        let target = [c.r * 100.0, c.g * 100.0, c.b * 100.0];
+
+        match self.mode {
+            DitherMode::Off => quantize_channels(target, &mut [0.0; 3]),
+            DitherMode::Temporal => {
+                let error = self.error.entry(output).or_insert([0.0; 3]);
+                quantize_channels(target, error)
+            },
+        }
+    }
+}
+
+/// Quantize `target` in percent plus the carried `error`, and keep the new error.
+fn quantize_channels(target: [f32; 3], error: &mut [f32; 3]) -> Percent3 {
+    let mut out = [0; 3];
+    for i in 0..3 {
+        let v = target[i] + error[i];
+        let q = v.round().clamp(0.0, 100.0);
+        error[i] = v - q;
+        out[i] = q as c_int;
+    }
+    (out[0], out[1], out[2])
+}
@@ -0,0 +1,310 @@
+//! Standard effects.
+//!
+//! Every effect is a plain struct with public settings, runs forever and,
+//! with the `serde` feature, can be saved and loaded with serde. Effects
+//! that move across the keyboard use the bitmap layout, see
+//! [`bitmap_position`](../../fn.bitmap_position.html), and leave the keys
+//! outside the bitmap as they are.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::{Color, Direction, FloatFrame, KeySet};
+//! use led::effect::Effect;
+//! use led::effect::builtin::{RainbowWave, Breathing};
+//! use std::time::Duration;
+//!
+//! # fn main() {
+//! let mut wave = RainbowWave::new(Direction::Horizontal);
+//! let mut logo = Breathing::new(Color::WHITE).mask(KeySet::logo());
+//!
+//! let mut frame = FloatFrame::new();
+//! wave.render(Duration::from_millis(250), &mut frame);
+//! logo.render(Duration::from_millis(250), &mut frame);
+//! # }
+//! ```
+
+use bitmap::{Direction, gradient_position};
+use color::{Color, Gradient};
+use frame::FloatFrame;
+use keys::{ALL_KEYS, bitmap_position};
+use sys::{Key, BITMAP_WIDTH, BITMAP_HEIGHT};
+use effect::Effect;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// `t` as a fraction of `period`, 0 if the period is 0.
+fn cycles(t: Duration, period: Duration) -> f32 {
+    match period.as_nanos() {
+        0 => 0.0,
+        p => (t.as_nanos() % p) as f32 / p as f32,
+    }
+}
+
+/// Keys in the bitmap with their cell.
+fn cells() -> impl Iterator<Item = (Key, usize, usize)> {
+    ALL_KEYS.iter().filter_map(|&k| bitmap_position(k).map(|(x, y)| (k, x, y)))
+}
+
+/// Draw `color` over what is below `key` with `coverage` in [0,1].
+fn cover(frame: &mut FloatFrame, key: Key, color: Color, coverage: f32) {
+    if coverage > 0.0 {
+        let below = frame.get(key);
+        frame.set(key, below.lerp(color, coverage));
+    }
+}
+
+/// A single color on every key.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Static {
+    pub color: Color,
+}
+
+impl Static {
+    pub fn new(color: Color) -> Static {
+        Static { color }
+    }
+}
+
+impl Effect for Static {
+    fn render(&mut self, _: Duration, frame: &mut FloatFrame) {
+        frame.fill(self.color);
+    }
+}
+
+/// Every key fading in and out.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Breathing {
+    pub color: Color,
+    /// Time of one breath.
+    pub period: Duration,
+    /// Brightness at the bottom of a breath, in [0,1].
+    pub min: f32,
+}
+
+impl Breathing {
+    /// Breathing `color` every 4 seconds, down to black.
+    pub fn new(color: Color) -> Breathing {
+        Breathing { color, period: Duration::from_secs(4), min: 0.0 }
+    }
+}
+
+impl Effect for Breathing {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let wave = (1.0 - (2.0 * PI * cycles(t, self.period)).cos()) / 2.0;
+        let min = self.min.clamp(0.0, 1.0);
+        frame.fill(self.color * (min + (1.0 - min) * wave));
+    }
+}
+
+/// Every key cycling through the hues together.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpectrumCycle {
+    /// Time of one cycle through the hues.
+    pub period: Duration,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+impl Default for SpectrumCycle {
+    fn default() -> SpectrumCycle {
+        SpectrumCycle { period: Duration::from_secs(10), saturation: 1.0, value: 1.0 }
+    }
+}
+
+impl SpectrumCycle {
+    /// Full colors, cycling every 10 seconds.
+    pub fn new() -> SpectrumCycle {
+        SpectrumCycle::default()
+    }
+}
+
+impl Effect for SpectrumCycle {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        frame.fill(Color::from_hsv(360.0 * cycles(t, self.period), self.saturation, self.value));
+    }
+}
+
+/// Rainbows moving across the keyboard.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RainbowWave {
+    /// Which way the wave moves.
+    pub direction: Direction,
+    /// Time for the wave to move one rainbow on.
+    pub period: Duration,
+    /// Number of rainbows across the keyboard.
+    pub repeats: f32,
+}
+
+impl RainbowWave {
+    /// One rainbow across the keyboard, moving on every 2 seconds.
+    pub fn new(direction: Direction) -> RainbowWave {
+        RainbowWave { direction, period: Duration::from_secs(2), repeats: 1.0 }
+    }
+}
+
+impl Effect for RainbowWave {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let position = gradient_position(self.direction);
+        let phase = cycles(t, self.period);
+        for (key, x, y) in cells() {
+            let hue = 360.0 * (position(x, y) * self.repeats - phase);
+            frame.set(key, Color::from_hsv(hue, 1.0, 1.0));
+        }
+    }
+}
+
+/// A gradient moving across the keyboard, wrapping around at its end.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GradientScroll {
+    pub gradient: Gradient,
+    /// Which way the gradient moves.
+    pub direction: Direction,
+    /// Time for the gradient to move once across the keyboard.
+    pub period: Duration,
+}
+
+impl GradientScroll {
+    /// `gradient` moving across in 5 seconds.
+    pub fn new(gradient: Gradient, direction: Direction) -> GradientScroll {
+        GradientScroll { gradient, direction, period: Duration::from_secs(5) }
+    }
+}
+
+impl Effect for GradientScroll {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let position = gradient_position(self.direction);
+        let phase = cycles(t, self.period);
+        for (key, x, y) in cells() {
+            let p = (position(x, y) - phase).rem_euclid(1.0);
+            frame.set(key, self.gradient.sample(p));
+        }
+    }
+}
+
+/// The hues around a center point, turning.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColorWheel {
+    /// Center column of the wheel, in cells.
+    pub x: f32,
+    /// Center row of the wheel, in cells.
+    pub y: f32,
+    /// Time of one turn, clockwise.
+    pub period: Duration,
+}
+
+impl Default for ColorWheel {
+    fn default() -> ColorWheel {
+        ColorWheel {
+            x: (BITMAP_WIDTH - 1) as f32 / 2.0,
+            y: (BITMAP_HEIGHT - 1) as f32 / 2.0,
+            period: Duration::from_secs(4),
+        }
+    }
+}
+
+impl ColorWheel {
+    /// Wheel at the center of the keyboard, turning every 4 seconds.
+    pub fn new() -> ColorWheel {
+        ColorWheel::default()
+    }
+}
+
+impl Effect for ColorWheel {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let turn = 360.0 * cycles(t, self.period);
+        for (key, x, y) in cells() {
+            let angle = (y as f32 - self.y).atan2(x as f32 - self.x).to_degrees();
+            frame.set(key, Color::from_hsv(angle - turn, 1.0, 1.0));
+        }
+    }
+}
+
+/// Random keys lighting up and fading out.
+///
+/// The stars are picked by hashing `seed`, so the same settings always
+/// render the same frame for the same time.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Starlight {
+    pub color: Color,
+    /// Fraction of the keys that light up in each twinkle, in [0,1].
+    pub density: f32,
+    /// Time a star takes to light up and fade out.
+    pub twinkle: Duration,
+    pub seed: u64,
+}
+
+impl Starlight {
+    /// A fifth of the keys twinkling in `color` for a second.
+    pub fn new(color: Color) -> Starlight {
+        Starlight { color, density: 0.2, twinkle: Duration::from_secs(1), seed: 0 }
+    }
+}
+
+/// SplitMix64, a well mixed hash of `x`.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// A hash of `x` in [0,1).
+fn unit(x: u64) -> f32 {
+    (mix(x) >> 40) as f32 / (1u64 << 24) as f32
+}
+
+impl Effect for Starlight {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let twinkle = self.twinkle.as_secs_f32();
+        if twinkle <= 0.0 {
+            return;
+        }
+        for (i, &key) in ALL_KEYS.iter().enumerate() {
+            let key_seed = mix(self.seed ^ i as u64);
+            // Every key twinkles on its own schedule.
+            let time = t.as_secs_f32() / twinkle + unit(key_seed);
+            let n = time.floor();
+            if unit(key_seed ^ mix(n as u64)) < self.density {
+                cover(frame, key, self.color, (PI * (time - n)).sin());
+            }
+        }
+    }
+}
+
+/// A light sweeping from side to side, "Knight Rider" style.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Scanner {
+    pub color: Color,
+    /// Half width of the light in columns, it fades out linearly.
+    pub width: f32,
+    /// Time to sweep there and back.
+    pub period: Duration,
+}
+
+impl Scanner {
+    /// A light three columns wide, sweeping every 2 seconds.
+    pub fn new(color: Color) -> Scanner {
+        Scanner { color, width: 2.0, period: Duration::from_secs(2) }
+    }
+}
+
+impl Effect for Scanner {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let phase = cycles(t, self.period);
+        let sweep = 1.0 - (2.0 * phase - 1.0).abs();
+        let center = sweep * (BITMAP_WIDTH - 1) as f32;
+        let width = self.width.max(f32::EPSILON);
+        for (key, x, _) in cells() {
+            let coverage = 1.0 - (x as f32 - center).abs() / width;
+            cover(frame, key, self.color, coverage.max(0.0));
+        }
+    }
+}
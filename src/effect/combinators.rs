@@ -0,0 +1,308 @@
+//! Effects built from other effects.
+//!
+//! Single effects are adapted with the provided methods of
+//! [`Effect`](../trait.Effect.html), like `speed` and `mask`. Effects are
+//! combined with [`sequence`](fn.sequence.html), [`crossfade`](fn.crossfade.html)
+//! and [`blend`](fn.blend.html).
+//!
+//! Combinators that play an effect backwards or again need its end, they
+//! leave effects without a [duration](../trait.Effect.html#method.duration)
+//! as they are.
+
+use color::{BlendMode, Color};
+use effect::Effect;
+use frame::FloatFrame;
+use keys::KeySet;
+use std::fmt;
+use std::time::Duration;
+
+const ZERO: Duration = Duration::from_secs(0);
+
+/// Sum of `durations`, `None` if any is `None`.
+fn total(durations: &[Option<Duration>]) -> Option<Duration> {
+    durations.iter().try_fold(ZERO, |sum, &d| d.map(|d| sum + d))
+}
+
+/// Plays effects one after another, see [`sequence`](fn.sequence.html).
+pub struct Sequence {
+    effects: Vec<Box<dyn Effect>>,
+}
+
+/// Play `effects` one after another, holding the last frame of the last one.
+///
+/// An effect without a duration never ends, so the effects after it never play.
+pub fn sequence(effects: Vec<Box<dyn Effect>>) -> Sequence {
+    Sequence { effects }
+}
+
+impl Effect for Sequence {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let mut start = ZERO;
+        let last = self.effects.len().saturating_sub(1);
+        for (i, effect) in self.effects.iter_mut().enumerate() {
+            match effect.duration() {
+                Some(d) if i < last && t >= start + d => start += d,
+                Some(d) => return effect.render((t - start).min(d), frame),
+                None => return effect.render(t - start, frame),
+            }
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        let durations: Vec<_> = self.effects.iter().map(|e| e.duration()).collect();
+        total(&durations)
+    }
+}
+
+impl fmt::Debug for Sequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Sequence").field("effects", &self.effects.len()).finish()
+    }
+}
+
+/// Plays an effect `n` times, see [`Effect::loop_n`](../trait.Effect.html#method.loop_n).
+#[derive(Debug, Clone)]
+pub struct LoopN<E> {
+    pub(crate) effect: E,
+    pub(crate) n: u32,
+}
+
+impl<E: Effect> Effect for LoopN<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        match self.effect.duration() {
+            Some(d) if d > ZERO && self.n > 0 => {
+                let t = if t >= d * self.n { d } else { wrap(t, d) };
+                self.effect.render(t, frame)
+            },
+            Some(_) => {},
+            None => self.effect.render(t, frame),
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration().map(|d| d * self.n)
+    }
+}
+
+/// `t` modulo `d`.
+fn wrap(t: Duration, d: Duration) -> Duration {
+    let n = t.as_nanos() % d.as_nanos();
+    Duration::new((n / 1_000_000_000) as u64, (n % 1_000_000_000) as u32)
+}
+
+/// Plays an effect backwards, see [`Effect::reverse`](../trait.Effect.html#method.reverse).
+#[derive(Debug, Clone)]
+pub struct Reverse<E> {
+    pub(crate) effect: E,
+}
+
+impl<E: Effect> Effect for Reverse<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        match self.effect.duration() {
+            Some(d) => self.effect.render(d - t.min(d), frame),
+            None => self.effect.render(t, frame),
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration()
+    }
+}
+
+/// Plays an effect faster or slower, see [`Effect::speed`](../trait.Effect.html#method.speed).
+#[derive(Debug, Clone)]
+pub struct Speed<E> {
+    pub(crate) effect: E,
+    pub(crate) factor: f64,
+}
+
+impl<E: Effect> Effect for Speed<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let t = Duration::from_secs_f64(t.as_secs_f64() * self.factor);
+        let t = match self.effect.duration() {
+            Some(d) => t.min(d),
+            None => t,
+        };
+        self.effect.render(t, frame)
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration().map(|d| Duration::from_secs_f64(d.as_secs_f64() / self.factor))
+    }
+}
+
+/// Starts an effect later, see [`Effect::delay`](../trait.Effect.html#method.delay).
+#[derive(Debug, Clone)]
+pub struct Delay<E> {
+    pub(crate) effect: E,
+    pub(crate) delay: Duration,
+}
+
+impl<E: Effect> Effect for Delay<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        if t >= self.delay {
+            self.effect.render(t - self.delay, frame)
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration().map(|d| d + self.delay)
+    }
+}
+
+/// Limits an effect to some keys, see [`Effect::mask`](../trait.Effect.html#method.mask).
+#[derive(Debug, Clone)]
+pub struct Mask<E> {
+    pub(crate) effect: E,
+    pub(crate) keys: KeySet,
+}
+
+impl<E: Effect> Effect for Mask<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let mut out = *frame;
+        self.effect.render(t, &mut out);
+        for key in self.keys.iter() {
+            frame.set_linear(key, out.linear(key));
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration()
+    }
+}
+
+/// Maps the colors of an effect, see [`Effect::map_color`](../trait.Effect.html#method.map_color).
+#[derive(Clone)]
+pub struct MapColor<E, F> {
+    pub(crate) effect: E,
+    pub(crate) f: F,
+}
+
+impl<E: Effect, F: FnMut(Color) -> Color> Effect for MapColor<E, F> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        self.effect.render(t, frame);
+        let out = *frame;
+        let f = &mut self.f;
+        frame.paint(|key| f(out.get(key)));
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration()
+    }
+}
+
+impl<E: fmt::Debug, F> fmt::Debug for MapColor<E, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapColor").field("effect", &self.effect).finish()
+    }
+}
+
+/// Plays an effect forwards and then backwards, see [`Effect::ping_pong`](../trait.Effect.html#method.ping_pong).
+#[derive(Debug, Clone)]
+pub struct PingPong<E> {
+    pub(crate) effect: E,
+}
+
+impl<E: Effect> Effect for PingPong<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        match self.effect.duration() {
+            Some(d) if t <= d => self.effect.render(t, frame),
+            Some(d) => self.effect.render(d - (t - d).min(d), frame),
+            None => self.effect.render(t, frame),
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.effect.duration().map(|d| d * 2)
+    }
+}
+
+/// Fades from one effect into the next, see [`crossfade`](fn.crossfade.html).
+#[derive(Debug, Clone)]
+pub struct Crossfade<A, B> {
+    a: A,
+    b: B,
+    fade: Duration,
+}
+
+/// Play `a` and then `b`, fading from `a` to `b` over `fade`.
+///
+/// `b` starts `fade` before `a` ends, or at the start if `a` has no duration.
+/// The fade is in linear light.
+pub fn crossfade<A: Effect, B: Effect>(a: A, b: B, fade: Duration) -> Crossfade<A, B> {
+    Crossfade { a, b, fade }
+}
+
+impl<A: Effect, B: Effect> Crossfade<A, B> {
+    /// Time `b` starts at.
+    fn start(&self) -> Duration {
+        self.a.duration().map_or(ZERO, |d| d - self.fade.min(d))
+    }
+}
+
+impl<A: Effect, B: Effect> Effect for Crossfade<A, B> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let start = self.start();
+        if t < start {
+            return self.a.render(t, frame);
+        }
+
+        let progress = match self.fade > ZERO {
+            true => ((t - start).as_secs_f32() / self.fade.as_secs_f32()).min(1.0),
+            false => 1.0,
+        };
+        let mut b = *frame;
+        self.b.render(t - start, &mut b);
+        if progress < 1.0 {
+            let a_end = self.a.duration().map_or(t, |d| t.min(d));
+            self.a.render(a_end, frame);
+            frame.mix(&b, progress);
+        } else {
+            *frame = b;
+        }
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        match (self.a.duration(), self.b.duration()) {
+            (Some(_), Some(b)) => Some(self.start() + b.max(self.fade)),
+            _ => None,
+        }
+    }
+}
+
+/// Blends one effect onto another, see [`blend`](fn.blend.html).
+#[derive(Debug, Clone)]
+pub struct Blend<A, B> {
+    a: A,
+    b: B,
+    mode: BlendMode,
+}
+
+/// Play `a` and `b` together, with `b` rendered on black and blended onto `a` with `mode`.
+///
+/// Ends when both have ended.
+pub fn blend<A: Effect, B: Effect>(a: A, b: B, mode: BlendMode) -> Blend<A, B> {
+    Blend { a, b, mode }
+}
+
+impl<A: Effect, B: Effect> Effect for Blend<A, B> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        let clamp = |d: Option<Duration>| d.map_or(t, |d| t.min(d));
+        let (ta, tb) = (clamp(self.a.duration()), clamp(self.b.duration()));
+
+        self.a.render(ta, frame);
+        let mut top = FloatFrame::new();
+        self.b.render(tb, &mut top);
+
+        let base = *frame;
+        let mode = self.mode;
+        frame.paint(|key| mode.apply(base.get(key), top.get(key)));
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        match (self.a.duration(), self.b.duration()) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        }
+    }
+}
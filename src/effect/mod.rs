@@ -0,0 +1,148 @@
+//! Animated lighting effects.
+//!
+//! An [`Effect`](trait.Effect.html) renders itself for any point in time
+//! into a [`FloatFrame`](../struct.FloatFrame.html). Effects keep no clock
+//! of their own, so they can be rendered at fixed timestamps in tests, and an
+//! [`Animator`](../animator/struct.Animator.html) plays them in real time.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::{Color, FloatFrame, Key};
+//! use led::effect::{self, Effect};
+//! use std::time::Duration;
+//!
+//! # fn main() {
+//! // Fade the keyboard from black to white over a second.
+//! let mut fade = effect::from_fn(|t: Duration, frame: &mut FloatFrame| {
+//!     let v = t.as_secs_f32().min(1.0);
+//!     frame.fill(Color::new(v, v, v));
+//! }).with_duration(Duration::from_secs(1));
+//!
+//! let mut frame = FloatFrame::new();
+//! fade.render(Duration::from_millis(500), &mut frame);
+//! assert_eq!(frame.get(Key::A), Color::new(0.5, 0.5, 0.5));
+//! assert_eq!(fade.duration(), Some(Duration::from_secs(1)));
+//! # }
+//! ```
+
+use color::Color;
+use frame::FloatFrame;
+use keys::KeySet;
+use std::fmt;
+use std::time::Duration;
+
+pub mod builtin;
+pub mod combinators;
+
+pub use self::combinators::{sequence, crossfade, blend};
+use self::combinators::{LoopN, Reverse, Speed, Delay, Mask, MapColor, PingPong};
+
+/// An animation, rendered on demand for any time since its start.
+pub trait Effect {
+    /// Draw the effect at `t` since its start onto `frame`.
+    ///
+    /// `frame` holds what is below the effect, black when played by an
+    /// [`Animator`](../animator/struct.Animator.html). Keys the effect
+    /// doesn't light are left as they are.
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame);
+
+    /// How long the effect runs, `None` if it runs forever.
+    fn duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Play the effect `n` times.
+    fn loop_n(self, n: u32) -> LoopN<Self> where Self: Sized {
+        LoopN { effect: self, n }
+    }
+
+    /// Play the effect backwards.
+    fn reverse(self) -> Reverse<Self> where Self: Sized {
+        Reverse { effect: self }
+    }
+
+    /// Play the effect `factor` times as fast.
+    ///
+    /// # Panics
+    /// If `factor` is not above 0.
+    fn speed(self, factor: f32) -> Speed<Self> where Self: Sized {
+        assert!(factor > 0.0 && factor.is_finite(), "speed must be above 0, got {}", factor);
+        Speed { effect: self, factor: factor as f64 }
+    }
+
+    /// Start the effect after `delay`, leaving the frame as it is until then.
+    fn delay(self, delay: Duration) -> Delay<Self> where Self: Sized {
+        Delay { effect: self, delay }
+    }
+
+    /// Only let the effect change the keys in `keys`.
+    fn mask(self, keys: KeySet) -> Mask<Self> where Self: Sized {
+        Mask { effect: self, keys }
+    }
+
+    /// Pass every key of the frame through `f` after the effect rendered.
+    fn map_color<F: FnMut(Color) -> Color>(self, f: F) -> MapColor<Self, F> where Self: Sized {
+        MapColor { effect: self, f }
+    }
+
+    /// Play the effect forwards and then backwards.
+    fn ping_pong(self) -> PingPong<Self> where Self: Sized {
+        PingPong { effect: self }
+    }
+}
+
+impl<E: Effect + ?Sized> Effect for Box<E> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        (**self).render(t, frame)
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        (**self).duration()
+    }
+}
+
+impl<E: Effect + ?Sized> Effect for &mut E {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        (**self).render(t, frame)
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        (**self).duration()
+    }
+}
+
+/// Effect that runs forever, rendered by `f`.
+pub fn from_fn<F: FnMut(Duration, &mut FloatFrame)>(f: F) -> FromFn<F> {
+    FromFn { f, duration: None }
+}
+
+/// Effect from a closure, see [`from_fn`](fn.from_fn.html).
+#[derive(Clone)]
+pub struct FromFn<F> {
+    f: F,
+    duration: Option<Duration>,
+}
+
+impl<F> FromFn<F> {
+    /// End the effect after `duration`.
+    pub fn with_duration(mut self, duration: Duration) -> FromFn<F> {
+        self.duration = Some(duration);
+        self
+    }
+}
+
+impl<F: FnMut(Duration, &mut FloatFrame)> Effect for FromFn<F> {
+    fn render(&mut self, t: Duration, frame: &mut FloatFrame) {
+        (self.f)(t, frame)
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+}
+
+impl<F> fmt::Debug for FromFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FromFn").field("duration", &self.duration).finish()
+    }
+}
@@ -0,0 +1,120 @@
+//! Showing per-key frames on devices without per-key lighting.
+//!
+//! A bitmap only reaches `PERKEY_RGB` devices, RGB and monochrome devices show
+//! nothing of it. With a [`Reducer`](enum.Reducer.html) set through
+//! [`Driver::set_fallback`](../struct.Driver.html#method.set_fallback), every
+//! bitmap is also reduced to one representative color, which is sent to the
+//! `RGB` and `MONOCHROME` targets.
+
+use color::Color;
+use keys::{ALL_KEYS, bitmap_index};
+use sys::{BITMAP_BYTES_PER_KEY, BITMAP_SIZE};
+use std::fmt;
+
+/// Number of hue buckets used by [`Reducer::Dominant`](enum.Reducer.html#variant.Dominant).
+const HUE_BUCKETS: usize = 12;
+
+/// Reduces the colors of a frame to a single color.
+#[derive(Copy, Clone)]
+pub enum Reducer {
+    /// Mean of all colors.
+    Average,
+    /// Mean of the colors with the most common hue, weighted by saturation and value.
+    /// Falls back to the average for frames without saturated colors.
+    Dominant,
+    /// The brightest color, by its highest channel.
+    MaxBrightness,
+    /// User supplied reducer, never called with an empty slice.
+    Custom(fn(&[Color]) -> Color),
+}
+
+impl fmt::Debug for Reducer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Reducer::Average => f.write_str("Average"),
+            Reducer::Dominant => f.write_str("Dominant"),
+            Reducer::MaxBrightness => f.write_str("MaxBrightness"),
+            Reducer::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
+
+fn average(colors: &[Color]) -> Color {
+    let n = colors.len() as f32;
+    let (r, g, b) = colors.iter().fold((0.0, 0.0, 0.0), |acc, c| (acc.0 + c.r, acc.1 + c.g, acc.2 + c.b));
+    Color::new(r / n, g / n, b / n)
+}
+
+/// Hue in degrees and the weight of a color, `None` for grays.
+fn hue_weight(c: Color) -> Option<(f32, f32)> {
+    match c.to_hsv() {
+        (_, s, _) if s <= 0.0 => None,
+        (h, s, v) => Some((h, s * v)),
+    }
+}
+
+fn dominant(colors: &[Color]) -> Color {
+    let mut weights = [0.0f32; HUE_BUCKETS];
+    let mut sums = [(0.0f32, 0.0f32, 0.0f32); HUE_BUCKETS];
+
+    for &c in colors {
+        if let Some((h, w)) = hue_weight(c) {
+            let i = ((h / 360.0 * HUE_BUCKETS as f32) as usize).min(HUE_BUCKETS - 1);
+            weights[i] += w;
+            sums[i].0 += c.r * w;
+            sums[i].1 += c.g * w;
+            sums[i].2 += c.b * w;
+        }
+    }
+
+    let (i, &w) = weights.iter().enumerate()
+        .fold((0, &0.0), |best, cur| if cur.1 > best.1 { cur } else { best });
+    if w <= 0.0 {
+        return average(colors);
+    }
+    Color::new(sums[i].0 / w, sums[i].1 / w, sums[i].2 / w)
+}
+
+fn max_brightness(colors: &[Color]) -> Color {
+    let brightness = |c: &Color| c.r.max(c.g).max(c.b);
+    colors.iter().cloned()
+        .fold(colors[0], |best, c| if brightness(&c) > brightness(&best) { c } else { best })
+}
+
+impl Reducer {
+    /// Reduce `colors` to one color, black if there are none.
+    pub fn reduce(&self, colors: &[Color]) -> Color {
+        if colors.is_empty() {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        match *self {
+            Reducer::Average => average(colors),
+            Reducer::Dominant => dominant(colors),
+            Reducer::MaxBrightness => max_brightness(colors),
+            Reducer::Custom(f) => f(colors).clamp(),
+        }
+    }
+
+    /// Reduce the keys of a bitmap. Cells without a key are ignored.
+    ///
+    /// # Panics
+    /// If `bitmap` is not [`BITMAP_SIZE`](../constant.BITMAP_SIZE.html) bytes long.
+    pub fn reduce_bitmap(&self, bitmap: &[u8]) -> Color {
+        self.reduce(&bitmap_colors(bitmap))
+    }
+}
+
+/// Colors of the keys in a bitmap, in [`ALL_KEYS`](../constant.ALL_KEYS.html) order.
+///
+/// # Panics
+/// If `bitmap` is not [`BITMAP_SIZE`](../constant.BITMAP_SIZE.html) bytes long.
+pub fn bitmap_colors(bitmap: &[u8]) -> Vec<Color> {
+    assert_eq!(bitmap.len(), BITMAP_SIZE);
+    ALL_KEYS.iter()
+        .filter_map(|&k| bitmap_index(k))
+        .map(|i| {
+            let o = i * BITMAP_BYTES_PER_KEY;
+            Color::from([bitmap[o], bitmap[o + 1], bitmap[o + 2], bitmap[o + 3]])
+        })
+        .collect()
+}
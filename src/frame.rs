@@ -0,0 +1,123 @@
+//! Float render target covering every key.
+//!
+//! A [`FloatFrame`](struct.FloatFrame.html) holds linear light in `f32` for
+//! every key in [`ALL_KEYS`](../constant.ALL_KEYS.html), including the G-keys,
+//! logo and badge that a bitmap can't address. Effects render into it at full
+//! precision, and [`Driver::set_lighting_from_frame`](../struct.Driver.html#method.set_lighting_from_frame)
+//! quantizes it once, on the way out.
+//!
+//! ```
+//! # extern crate logitech_led as led;
+//! use led::{Color, FloatFrame, Key, KeySet};
+//!
+//! # fn main() {
+//! let mut frame = FloatFrame::filled(Color::BLUE);
+//! frame.fill_keys(KeySet::g_keys(), Color::RED);
+//! frame.set(Key::G_LOGO, Color::WHITE);
+//!
+//! assert_eq!(frame.get(Key::G_5), Color::RED);
+//! assert_eq!(frame.to_bitmap().key(Key::A), Some(Color::BLUE));
+//! # }
+//! ```
+
+use bitmap::Bitmap;
+use color::{Color, LinearRgb};
+use keys::{self, ALL_KEYS, KeySet, bitmap_position};
+use sys::Key;
+
+const BLACK: LinearRgb = LinearRgb { r: 0.0, g: 0.0, b: 0.0 };
+
+/// Linear light per key, in [`ALL_KEYS`](../constant.ALL_KEYS.html) order.
+///
+/// Values set with [`set_linear`](#method.set_linear) may leave [0,1], they
+/// are clamped when the frame is quantized.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FloatFrame {
+    keys: [LinearRgb; ALL_KEYS.len()],
+}
+
+impl FloatFrame {
+    /// Black frame.
+    pub fn new() -> FloatFrame {
+        FloatFrame { keys: [BLACK; ALL_KEYS.len()] }
+    }
+
+    /// Frame with every key set to `color`.
+    pub fn filled(color: Color) -> FloatFrame {
+        FloatFrame { keys: [color.to_linear(); ALL_KEYS.len()] }
+    }
+
+    /// Frame with the colors of the keys in `bitmap`, other keys are black.
+    pub fn from_bitmap(bitmap: &Bitmap) -> FloatFrame {
+        let mut frame = FloatFrame::new();
+        frame.paint(|key| bitmap.key(key).unwrap_or(Color::BLACK));
+        frame
+    }
+
+    /// The keys in the bitmap, rounded to bytes. Keys outside the bitmap are dropped.
+    pub fn to_bitmap(&self) -> Bitmap {
+        let mut bitmap = Bitmap::new();
+        for (key, color) in self.iter() {
+            if let Some((x, y)) = bitmap_position(key) {
+                bitmap.set(x, y, color);
+            }
+        }
+        bitmap
+    }
+
+    pub fn get(&self, key: Key) -> Color {
+        Color::from_linear(self.linear(key))
+    }
+
+    pub fn set(&mut self, key: Key, color: Color) {
+        self.set_linear(key, color.to_linear());
+    }
+
+    pub fn linear(&self, key: Key) -> LinearRgb {
+        self.keys[keys::index(key)]
+    }
+
+    pub fn set_linear(&mut self, key: Key, color: LinearRgb) {
+        self.keys[keys::index(key)] = color;
+    }
+
+    pub fn fill(&mut self, color: Color) {
+        self.keys = [color.to_linear(); ALL_KEYS.len()];
+    }
+
+    /// Set every key in `keys` to `color`.
+    pub fn fill_keys(&mut self, keys: KeySet, color: Color) {
+        let l = color.to_linear();
+        for key in keys.iter() {
+            self.set_linear(key, l);
+        }
+    }
+
+    /// Set every key to `f(key)`.
+    pub fn paint<F: FnMut(Key) -> Color>(&mut self, mut f: F) {
+        for (i, &key) in ALL_KEYS.iter().enumerate() {
+            self.keys[i] = f(key).to_linear();
+        }
+    }
+
+    /// Move every key `t` of the way to `other`, in linear light.
+    pub fn mix(&mut self, other: &FloatFrame, t: f32) {
+        let t = t.clamp(0.0, 1.0);
+        for (a, b) in self.keys.iter_mut().zip(other.keys.iter()) {
+            a.r += (b.r - a.r) * t;
+            a.g += (b.g - a.g) * t;
+            a.b += (b.b - a.b) * t;
+        }
+    }
+
+    /// Every key with its color, in [`ALL_KEYS`](../constant.ALL_KEYS.html) order.
+    pub fn iter(&self) -> impl Iterator<Item = (Key, Color)> + '_ {
+        ALL_KEYS.iter().cloned().zip(self.keys.iter().map(|&l| Color::from_linear(l)))
+    }
+}
+
+impl Default for FloatFrame {
+    fn default() -> FloatFrame {
+        FloatFrame::new()
+    }
+}
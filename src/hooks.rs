@@ -0,0 +1,161 @@
+//! Restoring the lighting when the process panics or is terminated.
+//!
+//! `Drop for Driver` only runs on an orderly shutdown. The hooks installed by
+//! [`Driver::install_restore_hooks`](../struct.Driver.html#method.install_restore_hooks)
+//! restore the saved lighting or shadow snapshot of every registered driver
+//! and shut down the SDK before the process goes away.
+
+use session::{self, Session};
+use Error;
+use ctrlc;
+use std::sync::{Arc, Weak, Mutex, Once, TryLockError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::panic;
+use std::process;
+use std::thread;
+
+/// Exit status used after SIGINT or Ctrl-C, 128 plus the signal number as shells report it.
+pub const INTERRUPT_EXIT_STATUS: i32 = 130;
+
+/// Exit status used after SIGTERM.
+pub const TERMINATE_EXIT_STATUS: i32 = 143;
+
+static SESSIONS: Mutex<Vec<Weak<Mutex<Session>>>> = Mutex::new(Vec::new());
+static PANIC_HOOK: Once = Once::new();
+static SIGNAL_HANDLER: AtomicBool = AtomicBool::new(false);
+
+/// Register `session` and make sure the panic hook and signal handler are installed.
+pub(crate) fn install(session: &Arc<Mutex<Session>>) -> Result<(), Error> {
+    {
+        let mut sessions = SESSIONS.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.retain(|s| s.upgrade().is_some());
+        if !sessions.iter().any(|s| s.as_ptr() == Arc::as_ptr(session)) {
+            sessions.push(Arc::downgrade(session));
+        }
+    }
+
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if ends_process() {
+                // The panicking thread may hold a session lock, don't wait for it.
+                restore_all(false);
+            }
+            previous(info);
+        }));
+    });
+
+    if !SIGNAL_HANDLER.swap(true, Ordering::SeqCst) {
+        let result = ctrlc::set_handler(|| {
+            restore_all(true);
+            process::exit(signal::exit_status());
+        });
+        if let Err(e) = result {
+            SIGNAL_HANDLER.store(false, Ordering::SeqCst);
+            return Err(Error::SignalHandler(e));
+        }
+        signal::record();
+    }
+    Ok(())
+}
+
+/// Whether a panic on the current thread takes the process down.
+///
+/// A panic unwinds past `main` only on the main thread, so panics on other
+/// threads leave the drivers alone. A panic on the main thread that is caught
+/// with `catch_unwind` can't be told apart and still shuts them down.
+fn ends_process() -> bool {
+    cfg!(panic = "abort") || thread::current().name() == Some("main")
+}
+
+/// ctrlc runs one handler for every signal, so remember which one arrived.
+#[cfg(unix)]
+mod signal {
+    use libc::{self, c_int};
+    use std::mem;
+    use std::ptr;
+    use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+    use super::INTERRUPT_EXIT_STATUS;
+
+    const SIGNALS: [c_int; 2] = [libc::SIGTERM, libc::SIGHUP];
+
+    static RECEIVED: AtomicI32 = AtomicI32::new(0);
+    /// The handlers ctrlc installed for `SIGNALS`.
+    static CHAINED: [AtomicUsize; 2] = [AtomicUsize::new(0), AtomicUsize::new(0)];
+
+    extern "C" fn handle(signal: c_int) {
+        RECEIVED.store(signal, Ordering::SeqCst);
+        if let Some(i) = SIGNALS.iter().position(|&s| s == signal) {
+            let handler = CHAINED[i].load(Ordering::SeqCst);
+            if handler != libc::SIG_DFL && handler != libc::SIG_IGN {
+                // ctrlc installs a plain handler, without SA_SIGINFO.
+                let handler: extern "C" fn(c_int) = unsafe { mem::transmute(handler) };
+                handler(signal);
+            }
+        }
+    }
+
+    /// Put `handle` in front of the handlers ctrlc installed.
+    pub fn record() {
+        for (i, &signal) in SIGNALS.iter().enumerate() {
+            unsafe {
+                let mut old: libc::sigaction = mem::zeroed();
+                if libc::sigaction(signal, ptr::null(), &mut old) != 0 ||
+                    old.sa_flags & libc::SA_SIGINFO != 0 {
+                    continue;
+                }
+                CHAINED[i].store(old.sa_sigaction, Ordering::SeqCst);
+
+                let mut action: libc::sigaction = mem::zeroed();
+                action.sa_sigaction = handle as extern "C" fn(c_int) as usize;
+                action.sa_flags = old.sa_flags;
+                action.sa_mask = old.sa_mask;
+                libc::sigaction(signal, &action, ptr::null_mut());
+            }
+        }
+    }
+
+    pub fn exit_status() -> i32 {
+        match RECEIVED.load(Ordering::SeqCst) {
+            0 => INTERRUPT_EXIT_STATUS,
+            signal => 128 + signal,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod signal {
+    use super::INTERRUPT_EXIT_STATUS;
+
+    pub fn record() {}
+
+    pub fn exit_status() -> i32 {
+        INTERRUPT_EXIT_STATUS
+    }
+}
+
+/// Restore and shut down every registered session that is still alive.
+fn restore_all(wait: bool) {
+    let sessions = match SESSIONS.try_lock() {
+        Ok(mut sessions) => ::std::mem::take(&mut *sessions),
+        Err(TryLockError::Poisoned(e)) => ::std::mem::take(&mut *e.into_inner()),
+        Err(TryLockError::WouldBlock) => return,
+    };
+
+    for session in sessions.iter().filter_map(Weak::upgrade) {
+        let mut session = if wait {
+            session::lock(&session)
+        } else {
+            match session.try_lock() {
+                Ok(s) => s,
+                Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                Err(TryLockError::WouldBlock) => continue,
+            }
+        };
+
+        if !session.shut_down {
+            session.restore();
+            session.shutdown();
+        }
+    }
+}
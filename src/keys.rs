@@ -0,0 +1,303 @@
+//! Sets of keys.
+
+use sys::{Key, BITMAP_WIDTH, BITMAP_HEIGHT};
+use std::iter::FromIterator;
+use std::fmt;
+
+/// Every key known to the SDK, in declaration order.
+pub const ALL_KEYS: [Key; 115] = [
+    Key::ESC, Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8,
+    Key::F9, Key::F10, Key::F11, Key::F12, Key::PRINT_SCREEN, Key::SCROLL_LOCK,
+    Key::PAUSE_BREAK,
+    Key::TILDE, Key::ONE, Key::TWO, Key::THREE, Key::FOUR, Key::FIVE, Key::SIX, Key::SEVEN,
+    Key::EIGHT, Key::NINE, Key::ZERO, Key::MINUS, Key::EQUALS, Key::BACKSPACE, Key::INSERT,
+    Key::HOME, Key::PAGE_UP, Key::NUM_LOCK, Key::NUM_SLASH, Key::NUM_ASTERISK,
+    Key::NUM_MINUS,
+    Key::TAB, Key::Q, Key::W, Key::E, Key::R, Key::T, Key::Y, Key::U, Key::I, Key::O,
+    Key::P, Key::OPEN_BRACKET, Key::CLOSE_BRACKET, Key::BACKSLASH, Key::KEYBOARD_DELETE,
+    Key::END, Key::PAGE_DOWN, Key::NUM_SEVEN, Key::NUM_EIGHT, Key::NUM_NINE, Key::NUM_PLUS,
+    Key::CAPS_LOCK, Key::A, Key::S, Key::D, Key::F, Key::G, Key::H, Key::J, Key::K, Key::L,
+    Key::SEMICOLON, Key::APOSTROPHE, Key::ENTER, Key::NUM_FOUR, Key::NUM_FIVE, Key::NUM_SIX,
+    Key::LEFT_SHIFT, Key::Z, Key::X, Key::C, Key::V, Key::B, Key::N, Key::M, Key::COMMA,
+    Key::PERIOD, Key::FORWARD_SLASH, Key::RIGHT_SHIFT, Key::ARROW_UP, Key::NUM_ONE,
+    Key::NUM_TWO, Key::NUM_THREE, Key::NUM_ENTER,
+    Key::LEFT_CONTROL, Key::LEFT_WINDOWS, Key::LEFT_ALT, Key::SPACE, Key::RIGHT_ALT,
+    Key::RIGHT_WINDOWS, Key::APPLICATION_SELECT, Key::RIGHT_CONTROL, Key::ARROW_LEFT,
+    Key::ARROW_DOWN, Key::ARROW_RIGHT, Key::NUM_ZERO, Key::NUM_PERIOD,
+    Key::G_1, Key::G_2, Key::G_3, Key::G_4, Key::G_5, Key::G_6, Key::G_7, Key::G_8,
+    Key::G_9, Key::G_LOGO, Key::G_BADGE,
+];
+
+/// Index of `key` in the bitmap, counted in keys, not bytes.
+///
+/// The bitmap is laid out like a full size keyboard, row by row. Keys outside
+/// the bitmap, like the G-keys and the logo, return `None`.
+pub fn bitmap_index(key: Key) -> Option<usize> {
+    let i = match key {
+        Key::ESC => 0,
+        Key::F1 => 1,
+        Key::F2 => 2,
+        Key::F3 => 3,
+        Key::F4 => 4,
+        Key::F5 => 5,
+        Key::F6 => 6,
+        Key::F7 => 7,
+        Key::F8 => 8,
+        Key::F9 => 9,
+        Key::F10 => 10,
+        Key::F11 => 11,
+        Key::F12 => 12,
+        Key::PRINT_SCREEN => 13,
+        Key::SCROLL_LOCK => 14,
+        Key::PAUSE_BREAK => 15,
+
+        Key::TILDE => 21,
+        Key::ONE => 22,
+        Key::TWO => 23,
+        Key::THREE => 24,
+        Key::FOUR => 25,
+        Key::FIVE => 26,
+        Key::SIX => 27,
+        Key::SEVEN => 28,
+        Key::EIGHT => 29,
+        Key::NINE => 30,
+        Key::ZERO => 31,
+        Key::MINUS => 32,
+        Key::EQUALS => 33,
+        Key::BACKSPACE => 34,
+        Key::INSERT => 35,
+        Key::HOME => 36,
+        Key::PAGE_UP => 37,
+        Key::NUM_LOCK => 38,
+        Key::NUM_SLASH => 39,
+        Key::NUM_ASTERISK => 40,
+        Key::NUM_MINUS => 41,
+
+        Key::TAB => 42,
+        Key::Q => 43,
+        Key::W => 44,
+        Key::E => 45,
+        Key::R => 46,
+        Key::T => 47,
+        Key::Y => 48,
+        Key::U => 49,
+        Key::I => 50,
+        Key::O => 51,
+        Key::P => 52,
+        Key::OPEN_BRACKET => 53,
+        Key::CLOSE_BRACKET => 54,
+        Key::BACKSLASH => 55,
+        Key::KEYBOARD_DELETE => 56,
+        Key::END => 57,
+        Key::PAGE_DOWN => 58,
+        Key::NUM_SEVEN => 59,
+        Key::NUM_EIGHT => 60,
+        Key::NUM_NINE => 61,
+        Key::NUM_PLUS => 62,
+
+        Key::CAPS_LOCK => 63,
+        Key::A => 64,
+        Key::S => 65,
+        Key::D => 66,
+        Key::F => 67,
+        Key::G => 68,
+        Key::H => 69,
+        Key::J => 70,
+        Key::K => 71,
+        Key::L => 72,
+        Key::SEMICOLON => 73,
+        Key::APOSTROPHE => 74,
+        Key::ENTER => 76,
+        Key::NUM_FOUR => 80,
+        Key::NUM_FIVE => 81,
+        Key::NUM_SIX => 82,
+
+        Key::LEFT_SHIFT => 84,
+        Key::Z => 86,
+        Key::X => 87,
+        Key::C => 88,
+        Key::V => 89,
+        Key::B => 90,
+        Key::N => 91,
+        Key::M => 92,
+        Key::COMMA => 93,
+        Key::PERIOD => 94,
+        Key::FORWARD_SLASH => 95,
+        Key::RIGHT_SHIFT => 97,
+        Key::ARROW_UP => 99,
+        Key::NUM_ONE => 101,
+        Key::NUM_TWO => 102,
+        Key::NUM_THREE => 103,
+        Key::NUM_ENTER => 104,
+
+        Key::LEFT_CONTROL => 105,
+        Key::LEFT_WINDOWS => 106,
+        Key::LEFT_ALT => 107,
+        Key::SPACE => 110,
+        Key::RIGHT_ALT => 116,
+        Key::RIGHT_WINDOWS => 117,
+        Key::APPLICATION_SELECT => 118,
+        Key::RIGHT_CONTROL => 119,
+        Key::ARROW_LEFT => 120,
+        Key::ARROW_DOWN => 121,
+        Key::ARROW_RIGHT => 122,
+        Key::NUM_ZERO => 123,
+        Key::NUM_PERIOD => 125,
+
+        _ => return None,
+    };
+    Some(i)
+}
+
+/// Column and row of `key` in the bitmap.
+pub fn bitmap_position(key: Key) -> Option<(usize, usize)> {
+    bitmap_index(key).map(|i| (i % BITMAP_WIDTH, i / BITMAP_WIDTH))
+}
+
+/// Key at column `x` and row `y` of the bitmap, if any.
+pub fn key_at(x: usize, y: usize) -> Option<Key> {
+    if x >= BITMAP_WIDTH || y >= BITMAP_HEIGHT {
+        return None;
+    }
+    let i = y * BITMAP_WIDTH + x;
+    ALL_KEYS.iter().cloned().find(|&k| bitmap_index(k) == Some(i))
+}
+
+pub(crate) fn index(key: Key) -> usize {
+    ALL_KEYS.iter().position(|&k| k == key).expect("key missing from ALL_KEYS")
+}
+
+/// Set of keys, stored as a bit set.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct KeySet(u128);
+
+impl KeySet {
+    /// The empty set.
+    pub fn empty() -> KeySet {
+        KeySet(0)
+    }
+
+    /// Every key.
+    pub fn all() -> KeySet {
+        KeySet((1 << ALL_KEYS.len()) - 1)
+    }
+
+    /// Keys that are part of the bitmap, i.e. every key but the G-keys, logo and badge.
+    pub fn bitmap() -> KeySet {
+        ALL_KEYS.iter().cloned().filter(|&k| bitmap_index(k).is_some()).collect()
+    }
+
+    /// The programmable G-keys, `G_1` to `G_9`.
+    pub fn g_keys() -> KeySet {
+        [
+            Key::G_1, Key::G_2, Key::G_3, Key::G_4, Key::G_5,
+            Key::G_6, Key::G_7, Key::G_8, Key::G_9,
+        ].iter().cloned().collect()
+    }
+
+    /// The G logo and badge.
+    pub fn logo() -> KeySet {
+        [Key::G_LOGO, Key::G_BADGE].iter().cloned().collect()
+    }
+
+    pub fn contains(&self, key: Key) -> bool {
+        self.0 & (1 << index(key)) != 0
+    }
+
+    /// Adds a key, returns `true` if it wasn't already in the set.
+    pub fn insert(&mut self, key: Key) -> bool {
+        let had = self.contains(key);
+        self.0 |= 1 << index(key);
+        !had
+    }
+
+    /// Removes a key, returns `true` if it was in the set.
+    pub fn remove(&mut self, key: Key) -> bool {
+        let had = self.contains(key);
+        self.0 &= !(1 << index(key));
+        had
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn union(self, other: KeySet) -> KeySet {
+        KeySet(self.0 | other.0)
+    }
+
+    pub fn intersection(self, other: KeySet) -> KeySet {
+        KeySet(self.0 & other.0)
+    }
+
+    pub fn difference(self, other: KeySet) -> KeySet {
+        KeySet(self.0 & !other.0)
+    }
+
+    /// Returns `true` if every key in `self` is in `other`.
+    pub fn is_subset(&self, other: KeySet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// The keys in the set, in [`ALL_KEYS`](constant.ALL_KEYS.html) order.
+    pub fn iter(&self) -> Iter {
+        Iter { set: *self, next: 0 }
+    }
+}
+
+impl FromIterator<Key> for KeySet {
+    fn from_iter<I: IntoIterator<Item = Key>>(iter: I) -> KeySet {
+        let mut set = KeySet::empty();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Key> for KeySet {
+    fn extend<I: IntoIterator<Item = Key>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl IntoIterator for &KeySet {
+    type Item = Key;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+impl fmt::Debug for KeySet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Iterator over the keys in a [`KeySet`](struct.KeySet.html).
+#[derive(Debug, Clone)]
+pub struct Iter {
+    set: KeySet,
+    next: usize,
+}
+
+impl Iterator for Iter {
+    type Item = Key;
+
+    fn next(&mut self) -> Option<Key> {
+        while self.next < ALL_KEYS.len() {
+            let i = self.next;
+            self.next += 1;
+            if self.set.0 & (1 << i) != 0 {
+                return Some(ALL_KEYS[i]);
+            }
+        }
+        None
+    }
+}
@@ -3,10 +3,60 @@
 //#![warn(missing_docs)]
 
 extern crate logitech_led_sys as sys;
+#[cfg(feature = "restore-hooks")]
+extern crate ctrlc;
+#[cfg(all(feature = "restore-hooks", unix))]
+extern crate libc;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
 
-mod color;
+pub mod color;
+pub mod backend;
+pub mod mock;
+mod retry;
+mod recovery;
+mod state;
+mod session;
+#[cfg(feature = "restore-hooks")]
+mod hooks;
+mod pacing;
+mod builder;
+mod zone;
+mod keys;
+pub mod devices;
+mod fallback;
+pub mod target;
+mod probe;
+mod bitmap;
+mod frame;
+pub mod compositor;
+pub mod effect;
+pub mod animator;
+pub mod calibration;
+pub mod dither;
 
-pub use color::{Color, BGRA};
+pub use color::{Color, ColorA, BGRA, BlendMode, Gradient};
+pub use backend::{Backend, Operation};
+pub use retry::{RetryPolicy, Metrics};
+pub use recovery::{RecoveryPolicy, Event, Health};
+pub use state::LightingState;
+#[cfg(feature = "restore-hooks")]
+pub use hooks::{INTERRUPT_EXIT_STATUS, TERMINATE_EXIT_STATUS};
+pub use pacing::Pacing;
+pub use builder::DriverBuilder;
+pub use zone::{Zone, ZoneTarget};
+pub use keys::{KeySet, ALL_KEYS, bitmap_index, bitmap_position, key_at};
+pub use fallback::Reducer;
+pub use target::Target;
+pub use probe::Probe;
+pub use bitmap::{Bitmap, Direction};
+pub use frame::FloatFrame;
+pub use compositor::Compositor;
+pub use effect::Effect;
+pub use animator::Animator;
+pub use calibration::Calibration;
+pub use dither::DitherMode;
 
 pub use sys::{
     Key, DeviceType,
@@ -14,56 +64,257 @@ pub use sys::{
     BITMAP_BYTES_PER_KEY, BITMAP_SIZE,
 };
 
-use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
+use std::fmt;
 use std::os::raw::c_int;
-use sys::{Library, DURATION_INFINITE};
+use sys::DURATION_INFINITE;
+use recovery::Listeners;
+use session::Session;
+use pacing::Pacer;
+use target::TargetKind;
+use backend::Percent3;
+use color::WhiteBalance;
+use dither::Dither;
+use std::sync::{Arc, Mutex};
 
-static INITIALIZED: AtomicBool = ATOMIC_BOOL_INIT;
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+/// Error returned by [`Driver`](struct.Driver.html) methods.
+///
+/// Variants for failed SDK calls carry the arguments of the call,
+/// so the error can be logged without extra context.
 #[derive(Debug)]
 pub enum Error {
     Init,
+    InitWithName { name: String },
     GetSdkVersion,
-    GetConfigOptionNumber,
-    GetConfigOptionBool,
-    GetConfigOptionColor,
-    GetConfigOptionKeyInput,
-    SetConfigOptionLabel,
-    SetTargetDevice,
+    GetConfigOptionNumber { path: String },
+    GetConfigOptionBool { path: String },
+    GetConfigOptionColor { path: String },
+    GetConfigOptionKeyInput { path: String },
+    SetConfigOptionLabel { path: String, label: String },
+    SetTargetDevice { device_type: DeviceType },
     SaveCurrentLighting,
-    SetLighting,
+    SetLighting { color: Color },
     RestoreLighting,
-    FlashLighting,
-    PulseLighting,
+    FlashLighting { color: Color, duration: Option<Duration>, interval: Duration },
+    PulseLighting { color: Color, duration: Option<Duration>, interval: Duration },
     StopEffects,
     SetLightingFromBitmap,
-    SetLightingForKeyWithScanCode,
-    SetLightingForKeyWithHidCode,
-    SetLightingForKeyWithQuartzCode,
-    SetLightingForKeyWithKeyName,
-    SaveLightingForKey,
-    RestoreLightingForKey,
-    ExcludeKeysFromBitmap,
-    FlashSingleKey,
-    PulseSingleKey,
-    StopEffectsOnKey,
+    SetLightingForKeyWithScanCode { code: c_int, color: Color },
+    SetLightingForKeyWithHidCode { code: c_int, color: Color },
+    SetLightingForKeyWithQuartzCode { code: c_int, color: Color },
+    SetLightingForKeyWithKeyName { key: Key, color: Color },
+    SaveLightingForKey { key: Key },
+    RestoreLightingForKey { key: Key },
+    ExcludeKeysFromBitmap { keys: Vec<Key> },
+    FlashSingleKey { key: Key, color: Color, duration: Option<Duration>, interval: Duration },
+    PulseSingleKey { key: Key, start: Color, finish: Color, duration: Duration, infinite: bool },
+    StopEffectsOnKey { key: Key },
+    SetLightingForTargetZone { zone: Zone, color: Color },
+    SetLightingFromBitmapForTargetDevice { target: ZoneTarget },
     Shutdown,
+    /// The loaded SDK doesn't export this optional function.
+    Unsupported(Operation),
     /// Unexpected NULL character
     NullCharacter,
     /// Failed to load LogitechLed.dll.
     LoadLibrary(std::io::Error),
     Utf16(std::string::FromUtf16Error),
+    /// Failed to install the termination signal handler.
+    #[cfg(feature = "restore-hooks")]
+    SignalHandler(ctrlc::Error),
+    /// Invalid combination of [`DriverBuilder`](struct.DriverBuilder.html) options.
+    InvalidConfig(&'static str),
 }
 
+/// Classification of an [`Error`](enum.Error.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The SDK rejected the call. The SDK does this spuriously, especially
+    /// right after initialization, so the call may succeed if repeated.
+    Retryable,
+    /// Repeating the call will fail the same way.
+    Permanent,
+}
+
+impl Error {
+    /// Classify the error as retryable or permanent.
+    ///
+    /// Failing to initialize or shut down the SDK, usually because LGS isn't
+    /// running, is permanent, as is a missing SDK function.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::Init |
+            Error::InitWithName { .. } |
+            Error::Shutdown |
+            Error::NullCharacter |
+            Error::LoadLibrary(_) |
+            Error::Utf16(_) |
+            Error::InvalidConfig(_) |
+            Error::Unsupported(_) => ErrorKind::Permanent,
+            #[cfg(feature = "restore-hooks")]
+            Error::SignalHandler(_) => ErrorKind::Permanent,
+            _ => ErrorKind::Retryable,
+        }
+    }
+
+    /// Returns `true` if repeating the failed call may succeed.
+    pub fn is_retryable(&self) -> bool {
+        self.kind() == ErrorKind::Retryable
+    }
+
+    /// The SDK function that failed, if the error came from the SDK.
+    pub fn operation(&self) -> Option<Operation> {
+        Some(match *self {
+            Error::Init => Operation::Init,
+            Error::InitWithName { .. } => Operation::InitWithName,
+            Error::GetSdkVersion => Operation::GetSdkVersion,
+            Error::GetConfigOptionNumber { .. } => Operation::GetConfigOptionNumber,
+            Error::GetConfigOptionBool { .. } => Operation::GetConfigOptionBool,
+            Error::GetConfigOptionColor { .. } => Operation::GetConfigOptionColor,
+            Error::GetConfigOptionKeyInput { .. } => Operation::GetConfigOptionKeyInput,
+            Error::SetConfigOptionLabel { .. } => Operation::SetConfigOptionLabel,
+            Error::SetTargetDevice { .. } => Operation::SetTargetDevice,
+            Error::SaveCurrentLighting => Operation::SaveCurrentLighting,
+            Error::SetLighting { .. } => Operation::SetLighting,
+            Error::RestoreLighting => Operation::RestoreLighting,
+            Error::FlashLighting { .. } => Operation::FlashLighting,
+            Error::PulseLighting { .. } => Operation::PulseLighting,
+            Error::StopEffects => Operation::StopEffects,
+            Error::SetLightingFromBitmap => Operation::SetLightingFromBitmap,
+            Error::SetLightingForKeyWithScanCode { .. } => Operation::SetLightingForKeyWithScanCode,
+            Error::SetLightingForKeyWithHidCode { .. } => Operation::SetLightingForKeyWithHidCode,
+            Error::SetLightingForKeyWithQuartzCode { .. } => Operation::SetLightingForKeyWithQuartzCode,
+            Error::SetLightingForKeyWithKeyName { .. } => Operation::SetLightingForKeyWithKeyName,
+            Error::SaveLightingForKey { .. } => Operation::SaveLightingForKey,
+            Error::RestoreLightingForKey { .. } => Operation::RestoreLightingForKey,
+            Error::ExcludeKeysFromBitmap { .. } => Operation::ExcludeKeysFromBitmap,
+            Error::FlashSingleKey { .. } => Operation::FlashSingleKey,
+            Error::PulseSingleKey { .. } => Operation::PulseSingleKey,
+            Error::StopEffectsOnKey { .. } => Operation::StopEffectsOnKey,
+            Error::SetLightingForTargetZone { .. } => Operation::SetLightingForTargetZone,
+            Error::SetLightingFromBitmapForTargetDevice { .. } => Operation::SetLightingFromBitmapForTargetDevice,
+            Error::Unsupported(op) => op,
+            Error::Shutdown => Operation::Shutdown,
+            Error::NullCharacter |
+            Error::LoadLibrary(_) |
+            Error::Utf16(_) |
+            Error::InvalidConfig(_) => return None,
+            #[cfg(feature = "restore-hooks")]
+            Error::SignalHandler(_) => return None,
+        })
+    }
+
+    /// Name of the SDK function that failed, if the error came from the SDK.
+    pub fn sdk_function(&self) -> Option<&'static str> {
+        self.operation().map(Operation::sdk_function)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::NullCharacter => return f.write_str("unexpected NULL character in string"),
+            Error::LoadLibrary(ref e) => return write!(f, "failed to load LogitechLed.dll: {}", e),
+            Error::Utf16(ref e) => return write!(f, "invalid UTF-16 returned by the SDK: {}", e),
+            #[cfg(feature = "restore-hooks")]
+            Error::SignalHandler(ref e) => return write!(f, "failed to install signal handler: {}", e),
+            Error::InvalidConfig(reason) => return write!(f, "invalid driver configuration: {}", reason),
+            Error::Unsupported(op) => return write!(f, "{} is not supported by this version of the SDK", op),
+            _ => {},
+        }
+
+        write!(f, "{} failed", self.sdk_function().unwrap())?;
+        match *self {
+            Error::InitWithName { ref name } =>
+                write!(f, " for application name {:?}", name),
+            Error::GetConfigOptionNumber { ref path } |
+            Error::GetConfigOptionBool { ref path } |
+            Error::GetConfigOptionColor { ref path } |
+            Error::GetConfigOptionKeyInput { ref path } =>
+                write!(f, " for config option {:?}", path),
+            Error::SetConfigOptionLabel { ref path, ref label } =>
+                write!(f, " for config option {:?} with label {:?}", path, label),
+            Error::SetTargetDevice { device_type } =>
+                write!(f, " for device type {:?}", device_type),
+            Error::SetLighting { color } =>
+                write!(f, " with color {:?}", color),
+            Error::FlashLighting { color, duration, interval } |
+            Error::PulseLighting { color, duration, interval } =>
+                write!(f, " with color {:?}, duration {:?} and interval {:?}", color, duration, interval),
+            Error::SetLightingForKeyWithScanCode { code, color } |
+            Error::SetLightingForKeyWithHidCode { code, color } |
+            Error::SetLightingForKeyWithQuartzCode { code, color } =>
+                write!(f, " for key code {:#x} with color {:?}", code, color),
+            Error::SetLightingForKeyWithKeyName { key, color } =>
+                write!(f, " for key {:?} with color {:?}", key, color),
+            Error::SaveLightingForKey { key } |
+            Error::RestoreLightingForKey { key } |
+            Error::StopEffectsOnKey { key } =>
+                write!(f, " for key {:?}", key),
+            Error::ExcludeKeysFromBitmap { ref keys } =>
+                write!(f, " for keys {:?}", keys),
+            Error::FlashSingleKey { key, color, duration, interval } =>
+                write!(f, " for key {:?} with color {:?}, duration {:?} and interval {:?}",
+                    key, color, duration, interval),
+            Error::SetLightingForTargetZone { zone, color } =>
+                write!(f, " for zone {} of {:?} with color {:?}", zone.index, zone.target, color),
+            Error::SetLightingFromBitmapForTargetDevice { target } =>
+                write!(f, " for {:?}", target),
+            Error::PulseSingleKey { key, start, finish, duration, infinite } =>
+                write!(f, " for key {:?} from {:?} to {:?}, duration {:?}, infinite {}",
+                    key, start, finish, duration, infinite),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::LoadLibrary(ref e) => Some(e),
+            Error::Utf16(ref e) => Some(e),
+            #[cfg(feature = "restore-hooks")]
+            Error::SignalHandler(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Handle to an initialized SDK.
+///
+/// The SDK is shut down when the driver is dropped.
 #[derive(Debug)]
 pub struct Driver {
-    lib: Library,
+    session: Arc<Mutex<Session>>,
+    app_name: Option<String>,
+    pacer: Pacer,
+    retry: RetryPolicy,
+    metrics: Metrics,
+    recovery: RecoveryPolicy,
+    failures: u32,
+    listeners: Listeners,
+    state: LightingState,
+    restore_on_drop: bool,
+    fallback: Option<Reducer>,
+    white_balance: Option<WhiteBalance>,
+    calibration: Option<Calibration>,
+    dither: Dither<Output>,
+}
+
+/// A light the driver quantizes colors for, to keep the dither error apart.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Output {
+    Lighting(DeviceType),
+    Key(Key),
+    Zone(Zone),
 }
 
 fn str_to_wchar(s: &str) -> Result<Vec<u16>, Error> {
     let mut v = s.encode_utf16().collect::<Vec<u16>>();
-    if v.iter().any(|&val| val == 0) {
+    if v.contains(&0) {
         return Err(Error::NullCharacter);
     }
     v.push(0);
@@ -75,252 +326,803 @@ fn duration_to_c_int(d: Duration) -> c_int {
         .and_then(|n| n.checked_add(d.subsec_nanos() as u64 / 1_000_000))
         .expect("Duration to c_int overflow");
 
-    assert!(n <= <c_int>::max_value() as u64, "Duration to c_int overflow");
+    assert!(n <= c_int::MAX as u64, "Duration to c_int overflow");
     n as c_int
 }
 
+/// Make an SDK call, retrying it according to `retry`.
+fn call_with_retry<F>(backend: &mut dyn Backend, pacer: &mut Pacer, retry: &RetryPolicy,
+    metrics: &mut Metrics, op: Operation, mut f: F) -> bool
+    where F: FnMut(&mut dyn Backend) -> bool
+{
+    let attempts = if retry.retries(op) { retry.attempts() } else { 1 };
+    for attempt in 0..attempts {
+        if attempt > 0 {
+            metrics.retries += 1;
+            let delay = retry.delay(attempt);
+            if delay > Duration::from_millis(0) {
+                std::thread::sleep(delay);
+            }
+        }
+
+        pacer.wait();
+        metrics.calls += 1;
+        if f(backend) {
+            return true;
+        }
+        metrics.failures += 1;
+    }
+
+    if attempts > 1 {
+        metrics.exhausted += 1;
+    }
+    false
+}
+
+/// Replay `state`, pacing and retrying every call like any other.
+fn replay_state(state: &LightingState, backend: &mut dyn Backend, pacer: &mut Pacer,
+    retry: &RetryPolicy, metrics: &mut Metrics) -> bool
+{
+    state.replay(|op, f| call_with_retry(backend, pacer, retry, metrics, op, f))
+}
+
+/// Call `LogiLedInit`, or `LogiLedInitWithName` if there is a name.
+fn init_backend(backend: &mut dyn Backend, app_name: &Option<String>, pacer: &mut Pacer,
+    retry: &RetryPolicy, metrics: &mut Metrics) -> bool
+{
+    match *app_name {
+        Some(ref name) => call_with_retry(backend, pacer, retry, metrics,
+            Operation::InitWithName, |b| b.init_with_name(name)),
+        None => call_with_retry(backend, pacer, retry, metrics, Operation::Init, |b| b.init()),
+    }
+}
+
 impl Driver {
+    /// Load `LogitechLed.dll` and initialize the SDK.
     pub fn init() -> Result<Driver, Error> {
-        let lib = Library::load().map_err(|e| Error::LoadLibrary(e))?;
-        
-        assert_eq!(INITIALIZED.swap(true, Ordering::SeqCst), false);
-        unsafe {
-            if !(lib.LogiLedInit)() {
+        Driver::builder().build()
+    }
+
+    /// Initialize the SDK through `backend`.
+    ///
+    /// # Panics
+    /// If the backend is [exclusive](backend/trait.Backend.html#method.exclusive)
+    /// and another exclusive driver is alive.
+    pub fn with_backend<B: Backend + 'static>(backend: B) -> Result<Driver, Error> {
+        Driver::builder().backend(backend).build()
+    }
+
+    /// Initialize the SDK with options, see [`DriverBuilder`](struct.DriverBuilder.html).
+    pub fn builder() -> DriverBuilder {
+        DriverBuilder::new()
+    }
+
+    /// Check whether the SDK can be loaded and initialized.
+    ///
+    /// The SDK is shut down again before returning, and is not initialized at all
+    /// if another driver is using it. Errors are reported in the result, this never panics.
+    pub fn probe() -> Probe {
+        Driver::builder().probe()
+    }
+
+    /// Initialize the SDK and wait for it to settle.
+    fn from_parts(mut backend: Box<dyn Backend>, app_name: Option<String>, pacing: Pacing,
+        retry: RetryPolicy) -> Result<Driver, Error>
+    {
+        let mut pacer = Pacer::new(pacing);
+        let mut metrics = Metrics::default();
+
+        let exclusive = backend.exclusive();
+        if exclusive {
+            assert!(!INITIALIZED.swap(true, Ordering::SeqCst));
+        }
+        if !init_backend(&mut *backend, &app_name, &mut pacer, &retry, &mut metrics) {
+            if exclusive {
                 INITIALIZED.store(false, Ordering::SeqCst);
-                return Err(Error::Init);
             }
+            return Err(match app_name {
+                Some(name) => Error::InitWithName { name },
+                None => Error::Init,
+            });
         }
+        pacer.settle();
 
         Ok(Driver {
-            lib: lib,
+            session: Arc::new(Mutex::new(Session::new(backend))),
+            app_name,
+            pacer,
+            retry,
+            metrics,
+            recovery: RecoveryPolicy::none(),
+            failures: 0,
+            listeners: Listeners::new(),
+            state: LightingState::default(),
+            restore_on_drop: false,
+            fallback: None,
+            white_balance: None,
+            calibration: None,
+            dither: Dither::new(DitherMode::Off),
         })
     }
 
-    pub fn set_type(&mut self, device_type: DeviceType) -> Result<(), Error> {
-        unsafe {
-            if !(self.lib.LogiLedSetTargetDevice)(device_type.bits()) {
-                return Err(Error::SetTargetDevice);
-            }
+    /// Set the delays inserted between SDK calls.
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacer.pacing = pacing;
+    }
+
+    /// The current pacing.
+    pub fn pacing(&self) -> &Pacing {
+        &self.pacer.pacing
+    }
+
+    /// Set how failed SDK calls are retried.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// The current retry policy.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Counters for the SDK calls made so far.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Reset the call counters.
+    pub fn reset_metrics(&mut self) {
+        self.metrics = Metrics::default();
+    }
+
+    /// Set how lost SDK ownership is detected and handled.
+    pub fn set_recovery_policy(&mut self, policy: RecoveryPolicy) {
+        self.recovery = policy;
+    }
+
+    /// The current recovery policy.
+    pub fn recovery_policy(&self) -> &RecoveryPolicy {
+        &self.recovery
+    }
+
+    /// Register a callback for driver [events](enum.Event.html).
+    pub fn on_event<F>(&mut self, listener: F)
+        where F: FnMut(&Event) + Send + 'static
+    {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Also show bitmaps on devices without per-key lighting.
+    ///
+    /// With a reducer set, [`set_lighting_from_bitmap`](#method.set_lighting_from_bitmap)
+    /// reduces each bitmap to one color and sets it on the `RGB` and `MONOCHROME`
+    /// devices of the current target, then switches back to the current target.
+    pub fn set_fallback(&mut self, reducer: Option<Reducer>) {
+        self.fallback = reducer;
+    }
+
+    /// The current fallback reducer.
+    pub fn fallback(&self) -> Option<Reducer> {
+        self.fallback
+    }
+
+    /// Correct the white of the devices, applied to every color
+    /// before conversion to percent, and to every bitmap.
+    pub fn set_white_balance(&mut self, white_balance: Option<WhiteBalance>) {
+        self.white_balance = white_balance;
+    }
+
+    /// The current white balance.
+    pub fn white_balance(&self) -> Option<WhiteBalance> {
+        self.white_balance
+    }
+
+    /// Correct the response of the devices, applied to every color and bitmap
+    /// after the white balance.
+    pub fn set_calibration(&mut self, calibration: Option<Calibration>) {
+        self.calibration = calibration;
+    }
+
+    /// The current calibration.
+    pub fn calibration(&self) -> Option<&Calibration> {
+        self.calibration.as_ref()
+    }
+
+    /// Set how colors are quantized to percent. Changing the mode resets the dither.
+    pub fn set_dither(&mut self, mode: DitherMode) {
+        self.dither.set_mode(mode);
+    }
+
+    /// The current dither mode.
+    pub fn dither(&self) -> DitherMode {
+        self.dither.mode()
+    }
+
+    /// Apply the white balance and calibration, before quantization.
+    fn correct(&self, color: Color) -> Color {
+        let color = match self.white_balance {
+            Some(wb) => wb.apply(color),
+            None => color,
+        };
+        match self.calibration {
+            Some(ref calibration) => calibration.apply(color),
+            None => color,
+        }
+    }
+
+    /// Apply the calibration lookup table, after quantization.
+    fn map_percent(&self, p: Percent3) -> Percent3 {
+        match self.calibration {
+            Some(ref calibration) => calibration.map_percent(p),
+            None => p,
+        }
+    }
+
+    /// Convert `color` to the percent the SDK takes.
+    fn percent(&self, color: Color) -> Percent3 {
+        self.map_percent(color::to_precent(self.correct(color)))
+    }
+
+    /// Like [`percent`](#method.percent), dithered over the frames sent to `output`.
+    fn dithered(&mut self, output: Output, color: Color) -> Percent3 {
+        let c = self.correct(color);
+        let p = self.dither.quantize(output, c);
+        self.map_percent(p)
+    }
+
+    /// The lighting last sent to the SDK, replayed after re-initialization.
+    pub fn lighting_state(&self) -> &LightingState {
+        &self.state
+    }
+
+    /// Probe the SDK by setting the current target device again.
+    ///
+    /// A failed probe is handled as lost ownership, regardless of the
+    /// failure threshold of the recovery policy.
+    pub fn check_health(&mut self) -> Health {
+        let target = self.state.target;
+        if self.call_once(Operation::SetTargetDevice, |b| b.set_target_device(target)) {
+            self.failures = 0;
+            return Health::Healthy;
+        }
+
+        self.failures += 1;
+        let failures = self.failures;
+        match self.ownership_lost(failures) {
+            true => Health::Recovered,
+            false => Health::Lost,
+        }
+    }
+
+    /// Run `LogiLedInit` again and replay the last known lighting.
+    pub fn reinitialize(&mut self) -> Result<(), Error> {
+        let initialized = {
+            let mut session = session::lock(&self.session);
+            init_backend(&mut *session.backend, &self.app_name, &mut self.pacer,
+                &self.retry, &mut self.metrics)
+        };
+        if !initialized {
+            self.listeners.emit(&Event::ReinitializeFailed);
+            return Err(match self.app_name {
+                Some(ref name) => Error::InitWithName { name: name.clone() },
+                None => Error::Init,
+            });
         }
+        self.pacer.settle();
+
+        self.failures = 0;
+        let replayed = {
+            let mut session = session::lock(&self.session);
+            session.shut_down = false;
+            replay_state(&self.state, &mut *session.backend, &mut self.pacer,
+                &self.retry, &mut self.metrics)
+        };
+        self.listeners.emit(&Event::Reinitialized { replayed });
         Ok(())
     }
 
+    /// Keep a copy of the current [lighting state](#method.lighting_state)
+    /// as the shadow snapshot.
+    ///
+    /// Unlike [`save_lighting`](#method.save_lighting) this does not depend on the SDK,
+    /// and the snapshot takes precedence over the saved lighting when the restore hooks run.
+    pub fn save_snapshot(&mut self) {
+        session::lock(&self.session).snapshot = Some(self.state.clone());
+    }
+
+    /// Replay the shadow snapshot, or restore the saved lighting if there is no snapshot.
+    pub fn restore_snapshot(&mut self) -> Result<(), Error> {
+        let snapshot = session::lock(&self.session).snapshot.clone();
+        match snapshot {
+            Some(snapshot) => {
+                let mut session = session::lock(&self.session);
+                let replayed = replay_state(&snapshot, &mut *session.backend, &mut self.pacer,
+                    &self.retry, &mut self.metrics);
+                match replayed {
+                    false => Err(Error::RestoreLighting),
+                    true => {
+                        drop(session);
+                        self.state = snapshot;
+                        Ok(())
+                    },
+                }
+            },
+            None => self.restore_lighting(),
+        }
+    }
+
+    /// Restore the lighting and shut down the SDK if the process panics
+    /// or receives a termination signal.
+    ///
+    /// The hooks replay the [shadow snapshot](#method.save_snapshot) if there is one,
+    /// otherwise they restore the lighting saved with [`save_lighting`](#method.save_lighting),
+    /// and then call `LogiLedShutdown`. After a signal the process exits with 128 plus
+    /// the signal number, [`INTERRUPT_EXIT_STATUS`](constant.INTERRUPT_EXIT_STATUS.html)
+    /// for SIGINT and Ctrl-C, [`TERMINATE_EXIT_STATUS`](constant.TERMINATE_EXIT_STATUS.html)
+    /// for SIGTERM. A panic hook that has already been installed is called after ours.
+    ///
+    /// Only panics that end the process run the hooks: panics on the main thread,
+    /// or on any thread when built with `panic = "abort"`. Panics on other threads
+    /// leave the driver working. A panic on the main thread caught with
+    /// `catch_unwind` still shuts the SDK down, so the driver fails afterwards.
+    ///
+    /// The hooks are process wide and only hold a weak reference to the driver,
+    /// so they do nothing once it has been dropped.
+    ///
+    /// Requires the `restore-hooks` feature.
+    #[cfg(feature = "restore-hooks")]
+    pub fn install_restore_hooks(&self) -> Result<(), Error> {
+        hooks::install(&self.session)
+    }
+
+    /// Emit the event and re-initialize if the policy says so.
+    /// Returns `true` if the SDK was re-initialized.
+    fn ownership_lost(&mut self, consecutive_failures: u32) -> bool {
+        self.listeners.emit(&Event::OwnershipLost { consecutive_failures });
+        self.recovery.reinitializes() && self.reinitialize().is_ok()
+    }
+
+    /// Make an SDK call, retrying it according to the retry policy,
+    /// without recovering from lost ownership.
+    fn call_once<F>(&mut self, op: Operation, f: F) -> bool
+        where F: FnMut(&mut dyn Backend) -> bool
+    {
+        let mut session = session::lock(&self.session);
+        call_with_retry(&mut *session.backend, &mut self.pacer, &self.retry, &mut self.metrics, op, f)
+    }
+
+    /// Make an SDK call, retrying it according to the retry policy.
+    ///
+    /// If the call fails and the recovery policy detects lost ownership,
+    /// the SDK is re-initialized and the call made once more.
+    fn call<F>(&mut self, op: Operation, mut f: F) -> bool
+        where F: FnMut(&mut dyn Backend) -> bool
+    {
+        if self.call_once(op, &mut f) {
+            self.failures = 0;
+            return true;
+        }
+
+        self.failures += 1;
+        let failures = self.failures;
+        if self.recovery.detects(failures) && self.ownership_lost(failures) {
+            return self.call_once(op, f);
+        }
+        false
+    }
+
+    /// Set the target device type to `T` and return a view
+    /// with only the calls that apply to it.
+    ///
+    /// ```no_run
+    /// # extern crate logitech_led as led;
+    /// # use led::{Driver, Color, Key};
+    /// # use led::target::PerKeyRgb;
+    /// # fn main() {
+    /// let mut driver = Driver::init().unwrap();
+    /// driver.target::<PerKeyRgb>().unwrap()
+    ///     .set_lighting_for_key(Key::W, Color::new(1.0, 0.0, 0.0)).unwrap();
+    /// # }
+    /// ```
+    pub fn target<'a, T: TargetKind>(&'a mut self) -> Result<Target<'a, T>, Error> {
+        Target::new(self)
+    }
+
+    pub fn set_type(&mut self, device_type: DeviceType) -> Result<(), Error> {
+        match self.call(Operation::SetTargetDevice, |b| b.set_target_device(device_type)) {
+            false => Err(Error::SetTargetDevice { device_type }),
+            true => {
+                self.state.target = device_type;
+                Ok(())
+            },
+        }
+    }
+
     pub fn config_option_num(&mut self, config_path: &str, default: f64) -> Result<f64, Error> {
         let ws = str_to_wchar(config_path)?;
         let mut val = default;
-        unsafe {
-            match (self.lib.LogiGetConfigOptionNumber)(ws.as_ptr(), (&mut val) as *mut _) {
-                false => Err(Error::GetConfigOptionNumber),
-                true  => Ok(val),
-            }
+        match self.call(Operation::GetConfigOptionNumber, |b| b.get_config_option_number(&ws, &mut val)) {
+            false => Err(Error::GetConfigOptionNumber { path: config_path.to_owned() }),
+            true  => Ok(val),
         }
     }
 
     pub fn config_option_bool(&mut self, config_path: &str, default: bool) -> Result<bool, Error> {
         let ws = str_to_wchar(config_path)?;
         let mut val = default;
-        unsafe {
-            match (self.lib.LogiGetConfigOptionBool)(ws.as_ptr(), (&mut val) as *mut _) {
-                false => Err(Error::GetConfigOptionBool),
-                true  => Ok(val),
-            }
+        match self.call(Operation::GetConfigOptionBool, |b| b.get_config_option_bool(&ws, &mut val)) {
+            false => Err(Error::GetConfigOptionBool { path: config_path.to_owned() }),
+            true  => Ok(val),
         }
     }
 
     pub fn config_option_color(&mut self, config_path: &str, default: Color) -> Result<Color, Error> {
-       let ws = str_to_wchar(config_path)?;
-       let mut c = color::to_precent(default);
-       unsafe {
-            match (self.lib.LogiGetConfigOptionColor)(ws.as_ptr(),
-                (&mut c.0) as *mut _, (&mut c.1) as *mut _, (&mut c.2) as *mut _)
-            {
-                false => Err(Error::GetConfigOptionColor),
-                true  => Ok(color::from_precent(c)),
-            }
+        let ws = str_to_wchar(config_path)?;
+        let mut c = color::to_precent(default);
+        match self.call(Operation::GetConfigOptionColor, |b| b.get_config_option_color(&ws, &mut c)) {
+            false => Err(Error::GetConfigOptionColor { path: config_path.to_owned() }),
+            true  => Ok(color::from_precent(c)),
         }
     }
 
     pub fn config_option_label(&mut self, config_path: &str, lable: &str) -> Result<String, Error> {
         let path_ws = str_to_wchar(config_path)?;
         let mut lable_ws = str_to_wchar(lable)?;
-        
-        unsafe {
-            // This might write over lable_ws, we unfortunately do not know the size of that string,
-            // let's hope it's smaller then this.
-            const LABEL_LEN: usize = 512;
-            lable_ws.reserve(LABEL_LEN);
-            lable_ws.set_len(LABEL_LEN);
-            match (self.lib.LogiSetConfigOptionLabel)(path_ws.as_ptr(), lable_ws.as_mut_ptr())
-            {
-                false => Err(Error::SetConfigOptionLabel),
-                true  => {
-                    // Find the \0
-                    let mut n = 0;
-                    while lable_ws[n] != 0 {
-                        n += 1;
-                        if n >= LABEL_LEN {
-                            return Err(Error::NullCharacter)
-                        }
+
+        // This might write over lable_ws, we unfortunately do not know the size of that string,
+        // let's hope it's smaller then this.
+        const LABEL_LEN: usize = 512;
+        lable_ws.resize(LABEL_LEN, 0);
+        match self.call(Operation::SetConfigOptionLabel, |b| b.set_config_option_label(&path_ws, &mut lable_ws)) {
+            false => Err(Error::SetConfigOptionLabel {
+                path: config_path.to_owned(),
+                label: lable.to_owned(),
+            }),
+            true  => {
+                // Find the \0
+                let mut n = 0;
+                while lable_ws[n] != 0 {
+                    n += 1;
+                    if n >= LABEL_LEN {
+                        return Err(Error::NullCharacter)
                     }
+                }
 
-                    // To string without the \0
-                    String::from_utf16(&lable_ws[..(n - 1)]).map_err(|e| Error::Utf16(e))
-                },
-            }
+                // To string without the \0
+                String::from_utf16(&lable_ws[..(n - 1)]).map_err(Error::Utf16)
+            },
         }
     }
 
     pub fn set_lighting(&mut self, color: Color) -> Result<(), Error> {
-        let c = color::to_precent(color);
-        unsafe {
-            match (self.lib.LogiLedSetLighting)(c.0, c.1, c.2) {
-                false => Err(Error::SetLighting),
-                true => Ok(()),
-            }
+        let target = self.state.target;
+        let c = self.dithered(Output::Lighting(target), color);
+        match self.call(Operation::SetLighting, |b| b.set_lighting(c)) {
+            false => Err(Error::SetLighting { color }),
+            true => {
+                self.state.set_lighting(c);
+                Ok(())
+            },
         }
     }
 
     pub fn save_lighting(&mut self) -> Result<(), Error> {
-        unsafe {
-            match (self.lib.LogiLedSaveCurrentLighting)() {
-                false => Err(Error::SaveCurrentLighting),
-                true => Ok(()),
-            }
+        match self.call(Operation::SaveCurrentLighting, |b| b.save_current_lighting()) {
+            false => Err(Error::SaveCurrentLighting),
+            true => {
+                session::lock(&self.session).saved = true;
+                Ok(())
+            },
         }
     }
 
     pub fn restore_lighting(&mut self) -> Result<(), Error> {
-        unsafe {
-            match (self.lib.LogiLedRestoreLighting)() {
-                false => Err(Error::RestoreLighting),
-                true => Ok(()),
-            }
+        match self.call(Operation::RestoreLighting, |b| b.restore_lighting()) {
+            false => Err(Error::RestoreLighting),
+            true => {
+                self.state.clear_lighting();
+                Ok(())
+            },
         }
     }
 
     pub fn flash_lighting(&mut self, color: Color, duration: Option<Duration>, interval: Duration) -> Result<(), Error> {
-       let c = color::to_precent(color);
-       let d = duration.map(|d| duration_to_c_int(d)).unwrap_or(DURATION_INFINITE);
-       let i = duration_to_c_int(interval);
-       unsafe {
-            match (self.lib.LogiLedFlashLighting)(c.0, c.1, c.2, d, i) {
-                false => Err(Error::FlashLighting),
-                true => Ok(()),
-            }
+        let c = self.percent(color);
+        let d = duration.map(duration_to_c_int).unwrap_or(DURATION_INFINITE);
+        let i = duration_to_c_int(interval);
+        match self.call(Operation::FlashLighting, |b| b.flash_lighting(c, d, i)) {
+            false => Err(Error::FlashLighting { color, duration, interval }),
+            true => Ok(()),
         }
     }
 
     pub fn pulse_lighting(&mut self, color: Color, duration: Option<Duration>, interval: Duration) -> Result<(), Error> {
-       let c = color::to_precent(color);
-       let d = duration.map(|d| duration_to_c_int(d)).unwrap_or(DURATION_INFINITE);
-       let i = duration_to_c_int(interval);
-       unsafe {
-            match (self.lib.LogiLedPulseLighting)(c.0, c.1, c.2, d, i) {
-                false => Err(Error::PulseLighting),
-                true => Ok(()),
-            }
+        let c = self.percent(color);
+        let d = duration.map(duration_to_c_int).unwrap_or(DURATION_INFINITE);
+        let i = duration_to_c_int(interval);
+        match self.call(Operation::PulseLighting, |b| b.pulse_lighting(c, d, i)) {
+            false => Err(Error::PulseLighting { color, duration, interval }),
+            true => Ok(()),
         }
     }
 
     pub fn stop_effects(&mut self) -> Result<(), Error> {
-        unsafe {
-            match (self.lib.LogiLedStopEffects)() {
-                false => Err(Error::StopEffects),
-                true => Ok(()),
-            }
+        match self.call(Operation::StopEffects, |b| b.stop_effects()) {
+            false => Err(Error::StopEffects),
+            true => Ok(()),
         }
     }
 
+    /// Set the keys in the bitmap, corrected for the white balance and calibration.
     pub fn set_lighting_from_bitmap(&mut self, bitmap: &[u8]) -> Result<(), Error> {
         assert_eq!(bitmap.len(), BITMAP_SIZE);
-        unsafe {
-            match (self.lib.LogiLedSetLightingFromBitmap)(bitmap.as_ptr()) {
-                false => Err(Error::SetLightingFromBitmap),
-                true => Ok(()),
+        match self.correct_bitmap(bitmap) {
+            Some(corrected) => self.send_bitmap(&corrected)?,
+            None => self.send_bitmap(bitmap)?,
+        }
+        match self.fallback {
+            Some(reducer) => self.set_fallback_lighting(reducer.reduce_bitmap(bitmap)),
+            None => Ok(()),
+        }
+    }
+
+    /// `bitmap` with every key corrected like a color sent per key, keeping the alpha.
+    /// `None` if there is nothing to correct.
+    fn correct_bitmap(&self, bitmap: &[u8]) -> Option<Vec<u8>> {
+        if self.white_balance.is_none() && self.calibration.is_none() {
+            return None;
+        }
+        // The lookup table maps percent, so with one the keys go through percent too.
+        let has_lut = self.calibration.as_ref().is_some_and(|c| c.lut.is_some());
+        let mut corrected = bitmap.to_vec();
+        for bgra in corrected.chunks_mut(BITMAP_BYTES_PER_KEY) {
+            let c = ColorA::from([bgra[0], bgra[1], bgra[2], bgra[3]]);
+            let color = match has_lut {
+                true => color::from_precent(self.percent(c.color())),
+                false => self.correct(c.color()),
+            };
+            bgra.copy_from_slice(&BGRA::from(color.with_alpha(c.a)));
+        }
+        Some(corrected)
+    }
+
+    fn send_bitmap(&mut self, bitmap: &[u8]) -> Result<(), Error> {
+        match self.call(Operation::SetLightingFromBitmap, |b| b.set_lighting_from_bitmap(bitmap)) {
+            false => Err(Error::SetLightingFromBitmap),
+            true => {
+                self.state.set_bitmap(bitmap);
+                Ok(())
+            },
+        }
+    }
+
+    /// Send `frame` through the output stage.
+    ///
+    /// Every key is corrected for white balance and calibration, dithered and
+    /// quantized to percent once. The keys in the bitmap are sent as one
+    /// bitmap, the G-keys, logo and badge with a call per key, skipped if
+    /// the key already shows that color. The [fallback](#method.set_fallback)
+    /// is reduced from the frame.
+    pub fn set_lighting_from_frame(&mut self, frame: &FloatFrame) -> Result<(), Error> {
+        self.send_frame(frame, false)
+    }
+
+    /// Like [`set_lighting_from_frame`](#method.set_lighting_from_frame), but
+    /// only sends the keys that changed.
+    ///
+    /// The changed keys of the bitmap are sent with a call per key or as a
+    /// whole bitmap, whichever takes fewer calls. What the keys show is taken
+    /// from the [`lighting_state`](#method.lighting_state), so this is exact
+    /// as long as the lighting is only changed through this driver.
+    pub fn update_lighting_from_frame(&mut self, frame: &FloatFrame) -> Result<(), Error> {
+        self.send_frame(frame, true)
+    }
+
+    fn send_frame(&mut self, frame: &FloatFrame, only_changes: bool) -> Result<(), Error> {
+        let percent = self.quantize_frame(frame);
+
+        let shown = match self.state.bitmap {
+            Some(ref bytes) if only_changes => Some(Bitmap::from_bytes(bytes)),
+            _ => None,
+        };
+        let changed: Vec<(Key, Percent3, Color)> = frame.iter().zip(percent.iter())
+            .filter_map(|((key, color), &p)| bitmap_position(key).map(|(x, y)| (key, x, y, p, color)))
+            .filter(|&(key, x, y, p, _)| {
+                let current = self.state.keys.get(&key).cloned()
+                    .or_else(|| shown.as_ref().map(|b| color::to_precent(b.get(x, y))));
+                current != Some(p)
+            })
+            .map(|(key, _, _, p, color)| (key, p, color))
+            .collect();
+
+        if shown.is_some() && changed.len() <= 1 {
+            for &(key, p, color) in &changed {
+                self.send_key(key, p, color)?;
             }
+        } else {
+            let mut bitmap = Bitmap::new();
+            for (&key, &p) in ALL_KEYS.iter().zip(percent.iter()) {
+                if let Some((x, y)) = bitmap_position(key) {
+                    bitmap.set(x, y, color::from_precent(p));
+                }
+            }
+            self.send_bitmap(&bitmap)?;
         }
+
+        for ((key, color), &p) in frame.iter().zip(percent.iter()) {
+            if bitmap_position(key).is_none() && self.state.keys.get(&key) != Some(&p) {
+                self.send_key(key, p, color)?;
+            }
+        }
+
+        match self.fallback {
+            Some(reducer) if !(only_changes && changed.is_empty()) => {
+                let colors: Vec<Color> = frame.iter()
+                    .filter(|&(key, _)| bitmap_position(key).is_some())
+                    .map(|(_, color)| color)
+                    .collect();
+                self.set_fallback_lighting(reducer.reduce(&colors))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// The output stage: every key of `frame` as the percent the SDK takes, in `ALL_KEYS` order.
+    fn quantize_frame(&mut self, frame: &FloatFrame) -> Vec<Percent3> {
+        frame.iter().map(|(key, color)| self.dithered(Output::Key(key), color)).collect()
+    }
+
+    /// Set `color` on the devices without per-key lighting.
+    fn set_fallback_lighting(&mut self, color: Color) -> Result<(), Error> {
+        let target = self.state.target;
+        let fallback = target & (DeviceType::RGB | DeviceType::MONOCHROME);
+        if fallback.is_empty() {
+            return Ok(());
+        }
+
+        self.set_type(fallback)?;
+        let res = self.set_lighting(color);
+        self.set_type(target)?;
+        res
     }
 
     pub fn set_lighting_for_key(&mut self, key: Key, color: Color) -> Result<(), Error> {
-        let c = color::to_precent(color);
-        unsafe {
-            match (self.lib.LogiLedSetLightingForKeyWithKeyName)(key, c.0, c.1, c.2) {
-                false => Err(Error::SetLightingForKeyWithKeyName),
-                true => Ok(()),
-            }
+        let c = self.dithered(Output::Key(key), color);
+        self.send_key(key, c, color)
+    }
+
+    /// Send the quantized `c` for `key`, `color` is what was asked for.
+    fn send_key(&mut self, key: Key, c: Percent3, color: Color) -> Result<(), Error> {
+        match self.call(Operation::SetLightingForKeyWithKeyName, |b| b.set_lighting_for_key(key, c)) {
+            false => Err(Error::SetLightingForKeyWithKeyName { key, color }),
+            true => {
+                self.state.keys.insert(key, c);
+                Ok(())
+            },
         }
     }
 
     pub fn save_lighting_for_key(&mut self, key: Key) -> Result<(), Error> {
-       unsafe {
-            match (self.lib.LogiLedSaveLightingForKey)(key) {
-                false => Err(Error::SaveLightingForKey),
-                true => Ok(()),
-            }
+        match self.call(Operation::SaveLightingForKey, |b| b.save_lighting_for_key(key)) {
+            false => Err(Error::SaveLightingForKey { key }),
+            true => Ok(()),
         }
     }
 
     pub fn restore_lighting_for_key(&mut self, key: Key) -> Result<(), Error> {
-       unsafe {
-            match (self.lib.LogiLedRestoreLightingForKey)(key) {
-                false => Err(Error::RestoreLightingForKey),
-                true => Ok(()),
-            }
+        match self.call(Operation::RestoreLightingForKey, |b| b.restore_lighting_for_key(key)) {
+            false => Err(Error::RestoreLightingForKey { key }),
+            true => Ok(()),
         }
     }
 
     pub fn exclude_keys_from_bitmap(&mut self, keys: &[Key]) -> Result<(), Error> {
-       unsafe {
-            match (self.lib.LogiLedExcludeKeysFromBitmap)(keys.as_ptr(), keys.len() as c_int) {
-                false => Err(Error::ExcludeKeysFromBitmap),
-                true => Ok(()),
-            }
+        match self.call(Operation::ExcludeKeysFromBitmap, |b| b.exclude_keys_from_bitmap(keys)) {
+            false => Err(Error::ExcludeKeysFromBitmap { keys: keys.to_vec() }),
+            true => {
+                self.state.excluded = keys.to_vec();
+                Ok(())
+            },
         }
     }
 
     pub fn flash_single_key(&mut self, key: Key, color: Color, duration: Option<Duration>, interval: Duration)
         -> Result<(), Error>
     {
-        let c = color::to_precent(color);
-        let d = duration.map(|d| duration_to_c_int(d)).unwrap_or(DURATION_INFINITE);
+        let c = self.percent(color);
+        let d = duration.map(duration_to_c_int).unwrap_or(DURATION_INFINITE);
         let i = duration_to_c_int(interval);
-        unsafe {
-            match (self.lib.LogiLedFlashSingleKey)(key, c.0, c.1, c.2, d, i) {
-                false => Err(Error::FlashSingleKey),
-                true => Ok(()),
-            }
+        match self.call(Operation::FlashSingleKey, |b| b.flash_single_key(key, c, d, i)) {
+            false => Err(Error::FlashSingleKey { key, color, duration, interval }),
+            true => Ok(()),
         }
     }
 
     pub fn pulse_single_key(&mut self, key: Key, start: Color, finish: Color,
         duration: Duration, infinite: bool) -> Result<(), Error>
     {
-        let s = color::to_precent(start);
-        let f = color::to_precent(finish);
+        let s = self.percent(start);
+        let f = self.percent(finish);
         let d = duration_to_c_int(duration);
-        unsafe {
-            match (self.lib.LogiLedPulseSingleKey)(
-                    key,
-                    s.0, s.1, s.2,
-                    f.0, f.1, f.2,
-                    d,
-                    infinite as c_int,
-                )
-            {
-                false => Err(Error::PulseSingleKey),
-                true => Ok(()),
-            }
+        match self.call(Operation::PulseSingleKey, |b| b.pulse_single_key(key, s, f, d, infinite)) {
+            false => Err(Error::PulseSingleKey { key, start, finish, duration, infinite }),
+            true => Ok(()),
         }
     }
 
     pub fn stop_effects_on_key(&mut self, key: Key)
         -> Result<(), Error>
     {
-       unsafe {
-            match (self.lib.LogiLedStopEffectsOnKey)(key) {
-                false => Err(Error::StopEffectsOnKey),
-                true => Ok(()),
-            }
+        match self.call(Operation::StopEffectsOnKey, |b| b.stop_effects_on_key(key)) {
+            false => Err(Error::StopEffectsOnKey { key }),
+            true => Ok(()),
+        }
+    }
+
+    /// Returns `true` if the loaded SDK implements `op`.
+    ///
+    /// Only [optional](backend/enum.Operation.html#method.is_optional) operations
+    /// may be unsupported.
+    pub fn supports(&self, op: Operation) -> bool {
+        session::lock(&self.session).backend.supports(op)
+    }
+
+    fn require(&self, op: Operation) -> Result<(), Error> {
+        match self.supports(op) {
+            true => Ok(()),
+            false => Err(Error::Unsupported(op)),
+        }
+    }
+
+    /// Version of the SDK as `(major, minor, build)`.
+    pub fn sdk_version(&mut self) -> Result<(c_int, c_int, c_int), Error> {
+        self.require(Operation::GetSdkVersion)?;
+        let mut v = (0, 0, 0);
+        match self.call(Operation::GetSdkVersion, |b| b.get_sdk_version(&mut v)) {
+            false => Err(Error::GetSdkVersion),
+            true => Ok(v),
+        }
+    }
+
+    /// Set the color of a single zone, on devices that have lighting zones.
+    pub fn set_lighting_for_zone(&mut self, zone: Zone, color: Color) -> Result<(), Error> {
+        self.require(Operation::SetLightingForTargetZone)?;
+        let c = self.dithered(Output::Zone(zone), color);
+        match self.call(Operation::SetLightingForTargetZone,
+            |b| b.set_lighting_for_target_zone(zone.target, zone.index as c_int, c))
+        {
+            false => Err(Error::SetLightingForTargetZone { zone, color }),
+            true => {
+                self.state.zones.insert(zone, c);
+                Ok(())
+            },
+        }
+    }
+
+    /// Set every zone of `target` at once from a bitmap with one BGRA entry
+    /// per zone, on SDKs that export `LogiLedSetLightingFromBitmapForTargetDevice`.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `bitmap` isn't a multiple of `BITMAP_BYTES_PER_KEY`.
+    pub fn set_lighting_from_bitmap_for_target(&mut self, target: ZoneTarget, bitmap: &[u8])
+        -> Result<(), Error>
+    {
+        assert_eq!(bitmap.len() % BITMAP_BYTES_PER_KEY, 0);
+        self.require(Operation::SetLightingFromBitmapForTargetDevice)?;
+        let corrected = self.correct_bitmap(bitmap);
+        let bitmap = corrected.as_deref().unwrap_or(bitmap);
+        match self.call(Operation::SetLightingFromBitmapForTargetDevice,
+            |b| b.set_lighting_from_bitmap_for_target_device(target, bitmap))
+        {
+            false => Err(Error::SetLightingFromBitmapForTargetDevice { target }),
+            true => {
+                for (i, bgra) in bitmap.chunks(BITMAP_BYTES_PER_KEY).enumerate() {
+                    let c = ColorA::from([bgra[0], bgra[1], bgra[2], bgra[3]]).color();
+                    self.state.zones.insert(Zone::new(target, i as u8), color::to_precent(c));
+                }
+                Ok(())
+            },
         }
     }
 }
@@ -328,13 +1130,21 @@ impl Driver {
 impl Drop for Driver {
     /// Kills the applet and frees memory used by the SDK
     fn drop(&mut self) {
-        unsafe {
-            (self.lib.LogiLedShutdown)();
+        let mut session = session::lock(&self.session);
+        if self.restore_on_drop && !session.shut_down {
+            session.backend.restore_lighting();
+        }
+        session.shutdown();
+        if session.backend.exclusive() {
+            INITIALIZED.store(false, Ordering::SeqCst);
         }
-        INITIALIZED.store(false, Ordering::SeqCst);
     }
 }
 
-//TODO: add is_supported
+/// Returns `true` if the SDK can be loaded and initialized, see [`Driver::probe`](struct.Driver.html#method.probe).
+pub fn is_supported() -> bool {
+    Driver::probe().is_supported()
+}
+
 //TODO: build script unix
 //TODO: docs
@@ -0,0 +1,308 @@
+//! In-memory backend for testing code that uses a [`Driver`](../struct.Driver.html).
+//!
+//! [`MockBackend`](struct.MockBackend.html) records every SDK call and can be
+//! scripted to fail calls, so retry and recovery logic can be exercised
+//! without the hardware or the Logitech Gaming Software installed.
+//! Clones share state, keep one to inspect the backend after handing
+//! the other to the driver.
+
+use backend::{Backend, Operation, Percent3};
+use sys::{Key, DeviceType, ZoneTarget};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::os::raw::c_int;
+
+/// SDK call recorded by the [`MockBackend`](struct.MockBackend.html).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    Init,
+    InitWithName(String),
+    GetConfigOptionNumber(String),
+    GetConfigOptionBool(String),
+    GetConfigOptionColor(String),
+    SetConfigOptionLabel(String),
+    SetTargetDevice(DeviceType),
+    SaveCurrentLighting,
+    SetLighting(Percent3),
+    RestoreLighting,
+    FlashLighting(Percent3, c_int, c_int),
+    PulseLighting(Percent3, c_int, c_int),
+    StopEffects,
+    SetLightingFromBitmap(Vec<u8>),
+    SetLightingForKey(Key, Percent3),
+    SaveLightingForKey(Key),
+    RestoreLightingForKey(Key),
+    ExcludeKeysFromBitmap(Vec<Key>),
+    FlashSingleKey(Key, Percent3, c_int, c_int),
+    PulseSingleKey(Key, Percent3, Percent3, c_int, bool),
+    StopEffectsOnKey(Key),
+    GetSdkVersion,
+    SetLightingForTargetZone(ZoneTarget, c_int, Percent3),
+    SetLightingFromBitmapForTargetDevice(ZoneTarget, Vec<u8>),
+    Shutdown,
+}
+
+impl Call {
+    /// The SDK function this call was made to.
+    pub fn operation(&self) -> Operation {
+        match *self {
+            Call::Init => Operation::Init,
+            Call::InitWithName(_) => Operation::InitWithName,
+            Call::GetConfigOptionNumber(_) => Operation::GetConfigOptionNumber,
+            Call::GetConfigOptionBool(_) => Operation::GetConfigOptionBool,
+            Call::GetConfigOptionColor(_) => Operation::GetConfigOptionColor,
+            Call::SetConfigOptionLabel(_) => Operation::SetConfigOptionLabel,
+            Call::SetTargetDevice(_) => Operation::SetTargetDevice,
+            Call::SaveCurrentLighting => Operation::SaveCurrentLighting,
+            Call::SetLighting(_) => Operation::SetLighting,
+            Call::RestoreLighting => Operation::RestoreLighting,
+            Call::FlashLighting(..) => Operation::FlashLighting,
+            Call::PulseLighting(..) => Operation::PulseLighting,
+            Call::StopEffects => Operation::StopEffects,
+            Call::SetLightingFromBitmap(_) => Operation::SetLightingFromBitmap,
+            Call::SetLightingForKey(..) => Operation::SetLightingForKeyWithKeyName,
+            Call::SaveLightingForKey(_) => Operation::SaveLightingForKey,
+            Call::RestoreLightingForKey(_) => Operation::RestoreLightingForKey,
+            Call::ExcludeKeysFromBitmap(_) => Operation::ExcludeKeysFromBitmap,
+            Call::FlashSingleKey(..) => Operation::FlashSingleKey,
+            Call::PulseSingleKey(..) => Operation::PulseSingleKey,
+            Call::StopEffectsOnKey(_) => Operation::StopEffectsOnKey,
+            Call::GetSdkVersion => Operation::GetSdkVersion,
+            Call::SetLightingForTargetZone(..) => Operation::SetLightingForTargetZone,
+            Call::SetLightingFromBitmapForTargetDevice(..) => Operation::SetLightingFromBitmapForTargetDevice,
+            Call::Shutdown => Operation::Shutdown,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Failure {
+    Next(usize),
+    Always,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    calls: Vec<Call>,
+    failures: HashMap<Operation, Failure>,
+    unsupported: HashSet<Operation>,
+    initialized: bool,
+    echo: bool,
+}
+
+/// SDK version reported by the mock.
+pub const MOCK_SDK_VERSION: (c_int, c_int, c_int) = (9, 0, 0);
+
+/// Backend that records calls instead of talking to the SDK.
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    state: Arc<Mutex<State>>,
+}
+
+fn from_wchar(s: &[u16]) -> String {
+    let n = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+    String::from_utf16_lossy(&s[..n])
+}
+
+impl MockBackend {
+    pub fn new() -> MockBackend {
+        MockBackend::default()
+    }
+
+    /// Make the next `n` calls to `op` fail.
+    pub fn fail_next(&self, op: Operation, n: usize) {
+        self.state.lock().unwrap().failures.insert(op, Failure::Next(n));
+    }
+
+    /// Make every call to `op` fail until [`clear_failures`](#method.clear_failures) is called.
+    pub fn fail_always(&self, op: Operation) {
+        self.state.lock().unwrap().failures.insert(op, Failure::Always);
+    }
+
+    /// Remove all scripted failures.
+    pub fn clear_failures(&self) {
+        self.state.lock().unwrap().failures.clear();
+    }
+
+    /// Behave like an older SDK that doesn't export the
+    /// [optional](../backend/enum.Operation.html#method.is_optional) function `op`.
+    pub fn unsupport(&self, op: Operation) {
+        self.state.lock().unwrap().unsupported.insert(op);
+    }
+
+    /// Every call made so far, including failed ones, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// Number of calls made to `op`, including failed ones.
+    pub fn count(&self, op: Operation) -> usize {
+        self.state.lock().unwrap().calls.iter().filter(|c| c.operation() == op).count()
+    }
+
+    /// Forget the recorded calls.
+    pub fn clear_calls(&self) {
+        self.state.lock().unwrap().calls.clear();
+    }
+
+    /// Print every call to stderr as `mock: <call>`.
+    ///
+    /// Useful when the calls have to be observed from outside the process,
+    /// e.g. when testing behavior that ends with the process exiting.
+    pub fn set_echo(&self, echo: bool) {
+        self.state.lock().unwrap().echo = echo;
+    }
+
+    /// Returns `true` between a successful `init` and `shutdown`.
+    pub fn is_initialized(&self) -> bool {
+        self.state.lock().unwrap().initialized
+    }
+
+    fn record(&self, call: Call) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let op = call.operation();
+        if state.echo {
+            eprintln!("mock: {:?}", call);
+        }
+        state.calls.push(call);
+
+        let fail = match state.failures.get(&op).cloned() {
+            Some(Failure::Always) => true,
+            Some(Failure::Next(n)) if n > 0 => {
+                state.failures.insert(op, Failure::Next(n - 1));
+                true
+            },
+            _ => false,
+        };
+        !fail
+    }
+}
+
+impl Backend for MockBackend {
+    fn supports(&self, op: Operation) -> bool {
+        !self.state.lock().unwrap().unsupported.contains(&op)
+    }
+
+    fn init(&mut self) -> bool {
+        let ok = self.record(Call::Init);
+        if ok {
+            self.state.lock().unwrap().initialized = true;
+        }
+        ok
+    }
+
+    fn init_with_name(&mut self, name: &str) -> bool {
+        if !self.supports(Operation::InitWithName) {
+            return self.init();
+        }
+        let ok = self.record(Call::InitWithName(name.to_owned()));
+        if ok {
+            self.state.lock().unwrap().initialized = true;
+        }
+        ok
+    }
+
+    fn get_config_option_number(&mut self, path: &[u16], _value: &mut f64) -> bool {
+        self.record(Call::GetConfigOptionNumber(from_wchar(path)))
+    }
+
+    fn get_config_option_bool(&mut self, path: &[u16], _value: &mut bool) -> bool {
+        self.record(Call::GetConfigOptionBool(from_wchar(path)))
+    }
+
+    fn get_config_option_color(&mut self, path: &[u16], _value: &mut Percent3) -> bool {
+        self.record(Call::GetConfigOptionColor(from_wchar(path)))
+    }
+
+    fn set_config_option_label(&mut self, path: &[u16], _label: &mut [u16]) -> bool {
+        self.record(Call::SetConfigOptionLabel(from_wchar(path)))
+    }
+
+    fn set_target_device(&mut self, device_type: DeviceType) -> bool {
+        self.record(Call::SetTargetDevice(device_type))
+    }
+
+    fn save_current_lighting(&mut self) -> bool {
+        self.record(Call::SaveCurrentLighting)
+    }
+
+    fn set_lighting(&mut self, color: Percent3) -> bool {
+        self.record(Call::SetLighting(color))
+    }
+
+    fn restore_lighting(&mut self) -> bool {
+        self.record(Call::RestoreLighting)
+    }
+
+    fn flash_lighting(&mut self, color: Percent3, duration: c_int, interval: c_int) -> bool {
+        self.record(Call::FlashLighting(color, duration, interval))
+    }
+
+    fn pulse_lighting(&mut self, color: Percent3, duration: c_int, interval: c_int) -> bool {
+        self.record(Call::PulseLighting(color, duration, interval))
+    }
+
+    fn stop_effects(&mut self) -> bool {
+        self.record(Call::StopEffects)
+    }
+
+    fn set_lighting_from_bitmap(&mut self, bitmap: &[u8]) -> bool {
+        self.record(Call::SetLightingFromBitmap(bitmap.to_vec()))
+    }
+
+    fn set_lighting_for_key(&mut self, key: Key, color: Percent3) -> bool {
+        self.record(Call::SetLightingForKey(key, color))
+    }
+
+    fn save_lighting_for_key(&mut self, key: Key) -> bool {
+        self.record(Call::SaveLightingForKey(key))
+    }
+
+    fn restore_lighting_for_key(&mut self, key: Key) -> bool {
+        self.record(Call::RestoreLightingForKey(key))
+    }
+
+    fn exclude_keys_from_bitmap(&mut self, keys: &[Key]) -> bool {
+        self.record(Call::ExcludeKeysFromBitmap(keys.to_vec()))
+    }
+
+    fn flash_single_key(&mut self, key: Key, color: Percent3, duration: c_int, interval: c_int) -> bool {
+        self.record(Call::FlashSingleKey(key, color, duration, interval))
+    }
+
+    fn pulse_single_key(&mut self, key: Key, start: Percent3, finish: Percent3,
+        duration: c_int, infinite: bool) -> bool
+    {
+        self.record(Call::PulseSingleKey(key, start, finish, duration, infinite))
+    }
+
+    fn stop_effects_on_key(&mut self, key: Key) -> bool {
+        self.record(Call::StopEffectsOnKey(key))
+    }
+
+    fn get_sdk_version(&mut self, version: &mut (c_int, c_int, c_int)) -> bool {
+        if !self.supports(Operation::GetSdkVersion) {
+            return false;
+        }
+        let ok = self.record(Call::GetSdkVersion);
+        if ok {
+            *version = MOCK_SDK_VERSION;
+        }
+        ok
+    }
+
+    fn set_lighting_for_target_zone(&mut self, target: ZoneTarget, zone: c_int, color: Percent3) -> bool {
+        self.supports(Operation::SetLightingForTargetZone) &&
+            self.record(Call::SetLightingForTargetZone(target, zone, color))
+    }
+
+    fn set_lighting_from_bitmap_for_target_device(&mut self, target: ZoneTarget, bitmap: &[u8]) -> bool {
+        self.supports(Operation::SetLightingFromBitmapForTargetDevice) &&
+            self.record(Call::SetLightingFromBitmapForTargetDevice(target, bitmap.to_vec()))
+    }
+
+    fn shutdown(&mut self) {
+        self.record(Call::Shutdown);
+        self.state.lock().unwrap().initialized = false;
+    }
+}
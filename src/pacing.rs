@@ -0,0 +1,80 @@
+//! Pacing of SDK calls.
+//!
+//! The SDK is full of race conditions. Calls made right after `LogiLedInit`,
+//! or in quick succession, fail silently and do nothing. A
+//! [`Pacing`](struct.Pacing.html) makes the [`Driver`](../struct.Driver.html)
+//! wait instead of every application sprinkling sleeps around its calls.
+
+use std::time::{Duration, Instant};
+use std::thread;
+
+/// Delays the driver inserts between SDK calls.
+///
+/// The default pacing never waits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Pacing {
+    settle: Duration,
+    min_interval: Duration,
+}
+
+impl Pacing {
+    /// Never wait.
+    pub fn none() -> Pacing {
+        Pacing::default()
+    }
+
+    /// Leave at least `min_interval` between the starts of two SDK calls.
+    pub fn new(min_interval: Duration) -> Pacing {
+        Pacing {
+            settle: Duration::from_millis(0),
+            min_interval,
+        }
+    }
+
+    /// Wait `settle` after `LogiLedInit` before making any other call.
+    pub fn settle(mut self, settle: Duration) -> Pacing {
+        self.settle = settle;
+        self
+    }
+
+    pub fn settle_time(&self) -> Duration {
+        self.settle
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+}
+
+/// Enforces a [`Pacing`](struct.Pacing.html).
+#[derive(Debug, Clone)]
+pub(crate) struct Pacer {
+    pub pacing: Pacing,
+    next: Option<Instant>,
+}
+
+impl Pacer {
+    pub fn new(pacing: Pacing) -> Pacer {
+        Pacer {
+            pacing,
+            next: None,
+        }
+    }
+
+    /// Wait until the next call is allowed and record that it is being made.
+    pub fn wait(&mut self) {
+        if let Some(next) = self.next {
+            let now = Instant::now();
+            if next > now {
+                thread::sleep(next - now);
+            }
+        }
+        self.next = Some(Instant::now() + self.pacing.min_interval);
+    }
+
+    /// Hold back the next call for the settle time.
+    pub fn settle(&mut self) {
+        let settled = Instant::now() + self.pacing.settle;
+        self.next = Some(self.next.map_or(settled, |next| next.max(settled)));
+    }
+}
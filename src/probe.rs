@@ -0,0 +1,89 @@
+//! Checking whether the SDK is usable, without keeping it initialized.
+
+use backend::{Backend, Operation};
+use pacing::{Pacing, Pacer};
+use retry::{RetryPolicy, Metrics};
+use std::os::raw::c_int;
+use std::sync::atomic::Ordering;
+use {Error, INITIALIZED, init_backend};
+
+/// Result of [`Driver::probe`](../struct.Driver.html#method.probe).
+#[derive(Debug)]
+pub struct Probe {
+    /// Why the library couldn't be loaded or initialized, if it couldn't.
+    pub error: Option<Error>,
+    /// The library was located and loaded.
+    pub loaded: bool,
+    /// Another driver has the SDK initialized, so it wasn't initialized again.
+    pub in_use: bool,
+    /// `LogiLedInit` succeeded. The SDK was shut down again afterwards.
+    pub initialized: bool,
+    /// Version of the SDK as `(major, minor, build)`, if the SDK reports it.
+    pub sdk_version: Option<(c_int, c_int, c_int)>,
+    /// Every operation and whether the library implements it.
+    pub operations: Vec<(Operation, bool)>,
+}
+
+impl Probe {
+    /// Returns `true` if the SDK can be used, or already is by another driver.
+    pub fn is_supported(&self) -> bool {
+        self.loaded && (self.initialized || self.in_use)
+    }
+
+    /// Returns `true` if the library implements `op`.
+    pub fn supports(&self, op: Operation) -> bool {
+        self.operations.iter().any(|&(o, s)| o == op && s)
+    }
+}
+
+/// Initialize `backend`, query its version and shut it down again.
+pub(crate) fn run(backend: Result<Box<dyn Backend>, Error>, app_name: &Option<String>,
+    pacing: Pacing, retry: &RetryPolicy) -> Probe
+{
+    let mut probe = Probe {
+        error: None,
+        loaded: false,
+        in_use: false,
+        initialized: false,
+        sdk_version: None,
+        operations: Vec::new(),
+    };
+
+    let mut backend = match backend {
+        Ok(backend) => backend,
+        Err(e) => {
+            probe.error = Some(e);
+            return probe;
+        },
+    };
+    probe.loaded = true;
+    probe.operations = Operation::ALL.iter().map(|&op| (op, backend.supports(op))).collect();
+
+    let exclusive = backend.exclusive();
+    if exclusive && INITIALIZED.swap(true, Ordering::SeqCst) {
+        probe.in_use = true;
+        return probe;
+    }
+
+    let mut pacer = Pacer::new(pacing);
+    let mut metrics = Metrics::default();
+    if init_backend(&mut *backend, app_name, &mut pacer, retry, &mut metrics) {
+        probe.initialized = true;
+
+        let mut v = (0, 0, 0);
+        if backend.supports(Operation::GetSdkVersion) && backend.get_sdk_version(&mut v) {
+            probe.sdk_version = Some(v);
+        }
+        backend.shutdown();
+    } else {
+        probe.error = Some(match *app_name {
+            Some(ref name) => Error::InitWithName { name: name.clone() },
+            None => Error::Init,
+        });
+    }
+
+    if exclusive {
+        INITIALIZED.store(false, Ordering::SeqCst);
+    }
+    probe
+}
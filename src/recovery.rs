@@ -0,0 +1,107 @@
+//! Detection of lost SDK ownership and automatic re-initialization.
+//!
+//! The SDK only lets one client control the lighting, the most recent one to
+//! initialize takes over. Restarting Logitech Gaming Software also invalidates
+//! the session. Either way, every call starts failing. A
+//! [`RecoveryPolicy`](struct.RecoveryPolicy.html) set on the
+//! [`Driver`](../struct.Driver.html) treats a run of failed calls as lost
+//! ownership, and can re-run `LogiLedInit` and replay the last known lighting.
+
+use std::fmt;
+
+/// When to consider SDK ownership lost and what to do about it.
+///
+/// The default policy never considers ownership lost.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RecoveryPolicy {
+    failure_threshold: u32,
+    reinitialize: bool,
+}
+
+impl RecoveryPolicy {
+    /// Never consider ownership lost.
+    pub fn none() -> RecoveryPolicy {
+        RecoveryPolicy::default()
+    }
+
+    /// Consider ownership lost after `failure_threshold` consecutive failed
+    /// calls, retries not counted. Does not re-initialize.
+    pub fn new(failure_threshold: u32) -> RecoveryPolicy {
+        RecoveryPolicy {
+            failure_threshold,
+            reinitialize: false,
+        }
+    }
+
+    /// Re-initialize the SDK and replay the last known lighting when ownership is lost.
+    pub fn reinitialize(mut self, reinitialize: bool) -> RecoveryPolicy {
+        self.reinitialize = reinitialize;
+        self
+    }
+
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+    }
+
+    pub fn reinitializes(&self) -> bool {
+        self.reinitialize
+    }
+
+    pub(crate) fn detects(&self, consecutive_failures: u32) -> bool {
+        self.failure_threshold > 0 && consecutive_failures >= self.failure_threshold
+    }
+}
+
+/// Event emitted by a [`Driver`](../struct.Driver.html).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// SDK calls keep failing, another client or an LGS restart probably took over.
+    OwnershipLost {
+        /// Consecutive failed calls that led to the detection.
+        consecutive_failures: u32,
+    },
+    /// The SDK was re-initialized and the last known lighting replayed.
+    Reinitialized {
+        /// `false` if some of the lighting could not be replayed.
+        replayed: bool,
+    },
+    /// `LogiLedInit` failed while trying to recover.
+    ReinitializeFailed,
+}
+
+/// Result of [`Driver::check_health`](../struct.Driver.html#method.check_health).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Health {
+    /// The SDK accepted the probe.
+    Healthy,
+    /// The probe failed, but the SDK was re-initialized.
+    Recovered,
+    /// The probe failed and the SDK was not re-initialized.
+    Lost,
+}
+
+type Listener = Box<dyn FnMut(&Event) + Send>;
+
+pub(crate) struct Listeners(Vec<Listener>);
+
+impl Listeners {
+    pub fn new() -> Listeners {
+        Listeners(Vec::new())
+    }
+
+    pub fn push(&mut self, listener: Listener) {
+        self.0.push(listener);
+    }
+
+    pub fn emit(&mut self, event: &Event) {
+        for listener in self.0.iter_mut() {
+            listener(event);
+        }
+    }
+}
+
+impl fmt::Debug for Listeners {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Listeners({})", self.0.len())
+    }
+}
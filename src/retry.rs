@@ -0,0 +1,99 @@
+//! Retrying of SDK calls that fail spuriously.
+//!
+//! The SDK functions return `false` without a reason, most often right after
+//! `LogiLedInit` or when called in quick succession. A
+//! [`RetryPolicy`](struct.RetryPolicy.html) set on the
+//! [`Driver`](../struct.Driver.html) repeats such calls with a backoff.
+
+use backend::Operation;
+use std::time::Duration;
+
+/// How failed SDK calls are retried.
+///
+/// The default policy makes a single attempt, i.e. never retries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    attempts: u32,
+    initial_backoff: Duration,
+    multiplier: u32,
+    max_backoff: Duration,
+    operations: Option<Vec<Operation>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::new(1)
+    }
+
+    /// Make up to `attempts` attempts in total, without delay between them.
+    /// Every operation is retried.
+    pub fn new(attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            attempts: attempts.max(1),
+            initial_backoff: Duration::from_millis(0),
+            multiplier: 1,
+            max_backoff: Duration::from_millis(0),
+            operations: None,
+        }
+    }
+
+    /// Wait `initial` before the first retry and multiply the delay by
+    /// `multiplier` for every following retry, up to `max`.
+    pub fn backoff(mut self, initial: Duration, multiplier: u32, max: Duration) -> RetryPolicy {
+        self.initial_backoff = initial;
+        self.multiplier = multiplier.max(1);
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Only retry the listed operations.
+    pub fn operations(mut self, operations: &[Operation]) -> RetryPolicy {
+        self.operations = Some(operations.to_vec());
+        self
+    }
+
+    /// Total number of attempts, including the first.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Returns `true` if failed calls to `op` are retried.
+    pub fn retries(&self, op: Operation) -> bool {
+        self.attempts > 1 && match self.operations {
+            Some(ref ops) => ops.contains(&op),
+            None => true,
+        }
+    }
+
+    /// Delay before retry number `retry`, starting at 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let mut delay = self.initial_backoff;
+        for _ in 1..retry {
+            if delay >= self.max_backoff {
+                break;
+            }
+            delay = delay.checked_mul(self.multiplier).unwrap_or(self.max_backoff);
+        }
+        delay.min(self.max_backoff)
+    }
+}
+
+/// Counters for the SDK calls made by a [`Driver`](../struct.Driver.html).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// SDK calls made, including retries.
+    pub calls: u64,
+    /// SDK calls that returned `false`.
+    pub failures: u64,
+    /// Calls that were repeated after a failure.
+    pub retries: u64,
+    /// Operations that still failed after the last attempt.
+    pub exhausted: u64,
+}
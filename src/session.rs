@@ -0,0 +1,52 @@
+//! SDK session shared between a [`Driver`](../struct.Driver.html) and the restore hooks.
+
+use backend::Backend;
+use state::LightingState;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+#[derive(Debug)]
+pub(crate) struct Session {
+    pub backend: Box<dyn Backend>,
+    /// `LogiLedSaveCurrentLighting` succeeded during this session.
+    pub saved: bool,
+    /// Shadow snapshot taken with `Driver::save_snapshot`.
+    pub snapshot: Option<LightingState>,
+    pub shut_down: bool,
+}
+
+impl Session {
+    pub fn new(backend: Box<dyn Backend>) -> Session {
+        Session {
+            backend,
+            saved: false,
+            snapshot: None,
+            shut_down: false,
+        }
+    }
+
+    /// Put the lighting back the way it was saved, preferring the shadow snapshot.
+    /// Returns `false` if there was nothing to restore or a call failed.
+    #[cfg(feature = "restore-hooks")]
+    pub fn restore(&mut self) -> bool {
+        let backend = &mut *self.backend;
+        match self.snapshot {
+            Some(ref snapshot) => snapshot.replay(|_, f| f(backend)),
+            None if self.saved => backend.restore_lighting(),
+            None => false,
+        }
+    }
+
+    /// Call `LogiLedShutdown`, unless it has already been called.
+    pub fn shutdown(&mut self) {
+        if !self.shut_down {
+            self.backend.shutdown();
+            self.shut_down = true;
+        }
+    }
+}
+
+/// Lock a session, ignoring poisoning. The session has no invariants
+/// a panic could break.
+pub(crate) fn lock(session: &Mutex<Session>) -> MutexGuard<'_, Session> {
+    session.lock().unwrap_or_else(PoisonError::into_inner)
+}
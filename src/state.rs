@@ -0,0 +1,105 @@
+//! Record of the lighting a [`Driver`](../struct.Driver.html) has sent to the SDK.
+//!
+//! The SDK forgets everything when it is re-initialized, for example after
+//! Logitech Gaming Software restarts. The driver keeps a
+//! [`LightingState`](struct.LightingState.html) so it can replay it.
+
+use backend::{Backend, Operation, Percent3};
+use sys::{Key, DeviceType};
+use zone::Zone;
+use keys::bitmap_index;
+use std::os::raw::c_int;
+use std::collections::BTreeMap;
+
+/// Last known lighting, in the values that were sent to the SDK.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightingState {
+    /// Current target device type.
+    pub target: DeviceType,
+    /// Whole-device colors, with the target they were set for, oldest first.
+    pub lighting: Vec<(DeviceType, Percent3)>,
+    /// Keys excluded from the bitmap.
+    pub excluded: Vec<Key>,
+    /// Last bitmap.
+    pub bitmap: Option<Vec<u8>>,
+    /// Per-key colors set after the last bitmap, and of keys outside the bitmap.
+    pub keys: BTreeMap<Key, Percent3>,
+    /// Zone colors.
+    pub zones: BTreeMap<Zone, Percent3>,
+}
+
+impl Default for LightingState {
+    fn default() -> LightingState {
+        LightingState {
+            target: DeviceType::ALL,
+            lighting: Vec::new(),
+            excluded: Vec::new(),
+            bitmap: None,
+            keys: BTreeMap::new(),
+            zones: BTreeMap::new(),
+        }
+    }
+}
+
+impl LightingState {
+    pub(crate) fn set_lighting(&mut self, color: Percent3) {
+        let target = self.target;
+        self.lighting.retain(|&(t, _)| !target.contains(t));
+        self.lighting.push((target, color));
+        self.zones.clear();
+        if target.contains(DeviceType::PERKEY_RGB) {
+            self.bitmap = None;
+            self.keys.clear();
+        }
+    }
+
+    pub(crate) fn set_bitmap(&mut self, bitmap: &[u8]) {
+        self.bitmap = Some(bitmap.to_vec());
+        let excluded = &self.excluded;
+        self.keys.retain(|&k, _| excluded.contains(&k) || bitmap_index(k).is_none());
+    }
+
+    /// Forget the lighting, the SDK restored a state we don't know.
+    pub(crate) fn clear_lighting(&mut self) {
+        self.lighting.clear();
+        self.bitmap = None;
+        self.keys.clear();
+        self.zones.clear();
+    }
+
+    /// Send the recorded state to a freshly initialized SDK.
+    ///
+    /// Every call is made through `send`, together with its operation, so the
+    /// caller can pace and retry it. Returns `false` if any call failed.
+    pub fn replay<F>(&self, mut send: F) -> bool
+        where F: FnMut(Operation, &mut dyn FnMut(&mut dyn Backend) -> bool) -> bool
+    {
+        let mut ok = true;
+        for &(target, color) in &self.lighting {
+            ok &= send(Operation::SetTargetDevice, &mut |b| b.set_target_device(target));
+            ok &= send(Operation::SetLighting, &mut |b| b.set_lighting(color));
+        }
+
+        if !self.excluded.is_empty() || self.bitmap.is_some() || !self.keys.is_empty() {
+            ok &= send(Operation::SetTargetDevice, &mut |b| b.set_target_device(DeviceType::PERKEY_RGB));
+            if !self.excluded.is_empty() {
+                ok &= send(Operation::ExcludeKeysFromBitmap, &mut |b| b.exclude_keys_from_bitmap(&self.excluded));
+            }
+            if let Some(ref bitmap) = self.bitmap {
+                ok &= send(Operation::SetLightingFromBitmap, &mut |b| b.set_lighting_from_bitmap(bitmap));
+            }
+            for (&key, &color) in &self.keys {
+                ok &= send(Operation::SetLightingForKeyWithKeyName, &mut |b| b.set_lighting_for_key(key, color));
+            }
+        }
+
+        for (zone, &color) in &self.zones {
+            ok &= send(Operation::SetLightingForTargetZone,
+                &mut |b| b.set_lighting_for_target_zone(zone.target, zone.index as c_int, color));
+        }
+
+        let target = self.target;
+        ok &= send(Operation::SetTargetDevice, &mut |b| b.set_target_device(target));
+        ok
+    }
+}
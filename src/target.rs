@@ -0,0 +1,144 @@
+//! Typed views of a [`Driver`](../struct.Driver.html) for one target device type.
+//!
+//! The SDK silently ignores per-key calls while the target is `RGB` or
+//! `MONOCHROME`. A [`Target`](struct.Target.html) only exposes the calls that
+//! apply to its device type, so such misuse doesn't compile:
+//!
+//! ```compile_fail
+//! # extern crate logitech_led as led;
+//! # use led::{Driver, Color, Key};
+//! # use led::target::Rgb;
+//! # fn main() {
+//! let mut driver = Driver::init().unwrap();
+//! let mut rgb = driver.target::<Rgb>().unwrap();
+//! rgb.set_lighting_for_key(Key::W, Color::new(1.0, 0.0, 0.0)).unwrap();
+//! # }
+//! ```
+
+use color::Color;
+use sys::{DeviceType, Key};
+use {Driver, Error};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A target device type, see [`Driver::target`](../struct.Driver.html#method.target).
+pub trait TargetKind: private::Sealed {
+    /// Device type set when the view is created.
+    const DEVICE_TYPE: DeviceType;
+}
+
+/// Devices with per-key RGB lighting.
+#[derive(Debug)]
+pub enum PerKeyRgb {}
+
+/// Devices with one RGB color.
+#[derive(Debug)]
+pub enum Rgb {}
+
+/// Devices with one single color light.
+#[derive(Debug)]
+pub enum Monochrome {}
+
+impl private::Sealed for PerKeyRgb {}
+impl private::Sealed for Rgb {}
+impl private::Sealed for Monochrome {}
+
+impl TargetKind for PerKeyRgb {
+    const DEVICE_TYPE: DeviceType = DeviceType::PERKEY_RGB;
+}
+
+impl TargetKind for Rgb {
+    const DEVICE_TYPE: DeviceType = DeviceType::RGB;
+}
+
+impl TargetKind for Monochrome {
+    const DEVICE_TYPE: DeviceType = DeviceType::MONOCHROME;
+}
+
+/// A [`Driver`](../struct.Driver.html) targeting devices of type `T`.
+///
+/// Whole-device calls are available for every target,
+/// bitmap and per-key calls only for [`PerKeyRgb`](enum.PerKeyRgb.html).
+#[derive(Debug)]
+pub struct Target<'a, T: TargetKind> {
+    driver: &'a mut Driver,
+    kind: PhantomData<T>,
+}
+
+impl<'a, T: TargetKind> Target<'a, T> {
+    pub(crate) fn new(driver: &'a mut Driver) -> Result<Target<'a, T>, Error> {
+        driver.set_type(T::DEVICE_TYPE)?;
+        Ok(Target { driver, kind: PhantomData })
+    }
+
+    /// The device type of this target.
+    pub fn device_type(&self) -> DeviceType {
+        T::DEVICE_TYPE
+    }
+
+    pub fn set_lighting(&mut self, color: Color) -> Result<(), Error> {
+        self.driver.set_lighting(color)
+    }
+
+    pub fn save_lighting(&mut self) -> Result<(), Error> {
+        self.driver.save_lighting()
+    }
+
+    pub fn restore_lighting(&mut self) -> Result<(), Error> {
+        self.driver.restore_lighting()
+    }
+
+    pub fn flash_lighting(&mut self, color: Color, duration: Option<Duration>, interval: Duration) -> Result<(), Error> {
+        self.driver.flash_lighting(color, duration, interval)
+    }
+
+    pub fn pulse_lighting(&mut self, color: Color, duration: Option<Duration>, interval: Duration) -> Result<(), Error> {
+        self.driver.pulse_lighting(color, duration, interval)
+    }
+
+    pub fn stop_effects(&mut self) -> Result<(), Error> {
+        self.driver.stop_effects()
+    }
+}
+
+impl<'a> Target<'a, PerKeyRgb> {
+    pub fn set_lighting_from_bitmap(&mut self, bitmap: &[u8]) -> Result<(), Error> {
+        self.driver.set_lighting_from_bitmap(bitmap)
+    }
+
+    pub fn set_lighting_for_key(&mut self, key: Key, color: Color) -> Result<(), Error> {
+        self.driver.set_lighting_for_key(key, color)
+    }
+
+    pub fn save_lighting_for_key(&mut self, key: Key) -> Result<(), Error> {
+        self.driver.save_lighting_for_key(key)
+    }
+
+    pub fn restore_lighting_for_key(&mut self, key: Key) -> Result<(), Error> {
+        self.driver.restore_lighting_for_key(key)
+    }
+
+    pub fn exclude_keys_from_bitmap(&mut self, keys: &[Key]) -> Result<(), Error> {
+        self.driver.exclude_keys_from_bitmap(keys)
+    }
+
+    pub fn flash_single_key(&mut self, key: Key, color: Color, duration: Option<Duration>, interval: Duration)
+        -> Result<(), Error>
+    {
+        self.driver.flash_single_key(key, color, duration, interval)
+    }
+
+    pub fn pulse_single_key(&mut self, key: Key, start: Color, finish: Color,
+        duration: Duration, infinite: bool) -> Result<(), Error>
+    {
+        self.driver.pulse_single_key(key, start, finish, duration, infinite)
+    }
+
+    pub fn stop_effects_on_key(&mut self, key: Key) -> Result<(), Error> {
+        self.driver.stop_effects_on_key(key)
+    }
+}
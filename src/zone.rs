@@ -0,0 +1,24 @@
+//! Zones of devices with more than one lighting zone.
+//!
+//! Headsets and mice like the G633, G933 and G900 can have more than one zone,
+//! e.g. a logo and a light strip, that the whole-device functions can't address
+//! separately. They are set with `LogiLedSetLightingForTargetZone`, or all at
+//! once with `LogiLedSetLightingFromBitmapForTargetDevice`, which are only
+//! available in newer versions of the SDK.
+
+pub use sys::ZoneTarget;
+
+/// A lighting zone on a device of a specific type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Zone {
+    /// Device type the zone belongs to.
+    pub target: ZoneTarget,
+    /// Zone number, starting at 0.
+    pub index: u8,
+}
+
+impl Zone {
+    pub fn new(target: ZoneTarget, index: u8) -> Zone {
+        Zone { target, index }
+    }
+}
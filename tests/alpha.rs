@@ -0,0 +1,56 @@
+extern crate logitech_led as led;
+
+use led::{Bitmap, BlendMode, Color, ColorA, Key, BGRA};
+
+#[test]
+fn bgra_conversions() {
+    let bgra: BGRA = [255, 0, 0, 51];
+    let c = ColorA::from(bgra);
+    assert_eq!(c, ColorA::new(0.0, 0.0, 1.0, 0.2));
+    assert_eq!(BGRA::from(c), bgra);
+
+    // Color premultiplies and drops the alpha.
+    assert_eq!(Color::from(bgra), Color::new(0.0, 0.0, 0.2));
+    assert_eq!(Color::from(bgra), c.premultiplied());
+    assert_eq!(BGRA::from(Color::from(bgra)), [51, 0, 0, 255]);
+
+    assert_eq!(ColorA::from_premultiplied(c.premultiplied(), c.a), c);
+    assert_eq!(ColorA::from_premultiplied(Color::WHITE, 0.0), ColorA::TRANSPARENT);
+    assert_eq!(ColorA::from(Color::RED), Color::RED.with_alpha(1.0));
+}
+
+#[test]
+fn over() {
+    let base = Color::BLUE.with_alpha(1.0);
+    assert_eq!(ColorA::TRANSPARENT.over(base, BlendMode::Normal), base);
+    assert_eq!(Color::RED.with_alpha(1.0).over(base, BlendMode::Normal), Color::RED.with_alpha(1.0));
+    assert_eq!(Color::RED.with_alpha(0.25).over(base, BlendMode::Normal), ColorA::new(0.25, 0.0, 0.75, 1.0));
+
+    // Blend modes only apply where the base is covered.
+    let top = Color::new(0.5, 0.5, 0.5).with_alpha(1.0);
+    assert_eq!(top.over(Color::WHITE.with_alpha(1.0), BlendMode::Multiply), top);
+    assert_eq!(top.over(ColorA::TRANSPARENT, BlendMode::Multiply), top);
+    assert_eq!(top.over(Color::WHITE.with_alpha(0.5), BlendMode::Multiply), top);
+
+    let half = Color::RED.with_alpha(0.5).over(Color::BLUE.with_alpha(0.5), BlendMode::Normal);
+    assert_eq!(half, ColorA::new(2.0 / 3.0, 0.0, 1.0 / 3.0, 0.75));
+}
+
+#[test]
+fn bitmap_overlay() {
+    let mut base = Bitmap::filled(Color::BLUE);
+    let mut top = Bitmap::transparent();
+    top.set_rgba(0, 0, Color::RED.with_alpha(1.0));
+    top.set_rgba(1, 0, Color::RED.with_alpha(0.5));
+
+    base.overlay(&top, BlendMode::Normal);
+    assert_eq!(base.get(0, 0), Color::RED);
+    assert!(base.get(1, 0).approx_eq(&Color::new(0.5, 0.0, 0.5), 1.0 / 255.0));
+    assert_eq!(base.get_rgba(1, 0).a, 1.0);
+    assert_eq!(base.key(Key::A), Some(Color::BLUE));
+
+    let untouched = Bitmap::filled(Color::BLUE);
+    let mut copy = untouched;
+    copy.overlay(&Bitmap::transparent(), BlendMode::Screen);
+    assert_eq!(copy, untouched);
+}
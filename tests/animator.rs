@@ -0,0 +1,90 @@
+extern crate logitech_led as led;
+
+mod common;
+
+use common::{ms, ramp};
+use led::{Driver, Color, FloatFrame, Key, Operation, Pacing};
+use led::animator::{Animator, Tick};
+use led::effect;
+use led::mock::{MockBackend, Call};
+use std::time::Duration;
+
+#[test]
+fn update_sends_the_cheaper_of_keys_and_bitmap() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    mock.clear_calls();
+
+    // Nothing is known to be shown yet, so the first frame is a full bitmap.
+    let mut frame = FloatFrame::filled(Color::BLUE);
+    driver.update_lighting_from_frame(&frame).unwrap();
+    assert_eq!(mock.count(Operation::SetLightingFromBitmap), 1);
+
+    mock.clear_calls();
+    driver.update_lighting_from_frame(&frame).unwrap();
+    assert_eq!(mock.calls(), vec![]);
+
+    frame.set(Key::W, Color::RED);
+    driver.update_lighting_from_frame(&frame).unwrap();
+    assert_eq!(mock.calls(), vec![Call::SetLightingForKey(Key::W, (100, 0, 0))]);
+
+    mock.clear_calls();
+    frame.set(Key::W, Color::BLUE);
+    frame.set(Key::S, Color::RED);
+    driver.update_lighting_from_frame(&frame).unwrap();
+    assert_eq!(mock.count(Operation::SetLightingFromBitmap), 1);
+    assert_eq!(mock.count(Operation::SetLightingForKeyWithKeyName), 0);
+}
+
+#[test]
+fn frames_follow_the_schedule() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    let mut animator = Animator::new(ramp(), 4);
+    assert_eq!(animator.interval(), ms(250));
+
+    assert_eq!(animator.advance(&mut driver, ms(0)).unwrap(), Tick::Frame(ms(0)));
+    assert_eq!(animator.advance(&mut driver, ms(100)).unwrap(), Tick::Wait(ms(150)));
+    assert_eq!(animator.advance(&mut driver, ms(260)).unwrap(), Tick::Frame(ms(250)));
+    assert_eq!(animator.frame().get(Key::A), Color::new(0.25, 0.0, 0.0));
+
+    // Behind by two frames.
+    assert_eq!(animator.advance(&mut driver, ms(990)).unwrap(), Tick::Frame(ms(750)));
+    assert_eq!(animator.skipped(), 1);
+
+    // The last frame is rendered at the end of the effect.
+    assert_eq!(animator.advance(&mut driver, ms(1400)).unwrap(), Tick::Frame(ms(1000)));
+    assert!(animator.is_finished());
+    assert_eq!(animator.advance(&mut driver, ms(1500)).unwrap(), Tick::Finished);
+    assert_eq!(animator.frames(), 4);
+    assert_eq!(mock.calls().last(), Some(&Call::SetLightingForKey(Key::A, (100, 0, 0))));
+
+    animator.reset();
+    assert_eq!(animator.advance(&mut driver, ms(0)).unwrap(), Tick::Frame(ms(0)));
+}
+
+#[test]
+fn pacing_limits_the_frame_rate() {
+    let driver = Driver::builder()
+        .backend(MockBackend::new())
+        .pacing(Pacing::new(ms(100)))
+        .build()
+        .unwrap();
+    let animator = Animator::new(ramp(), 60);
+    assert_eq!(animator.interval_for(&driver), ms(100));
+}
+
+#[test]
+fn run_plays_to_the_end() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    let short = effect::from_fn(|t: Duration, frame: &mut FloatFrame| {
+        frame.fill(Color::new(t.as_secs_f32() * 10.0, 0.0, 0.0));
+    }).with_duration(ms(50));
+
+    let mut animator = Animator::new(short, 100);
+    animator.run(&mut driver).unwrap();
+    assert!(animator.is_finished());
+    assert!(animator.frames() >= 2);
+    assert_eq!(animator.frame().get(Key::A), Color::new(0.5, 0.0, 0.0));
+}
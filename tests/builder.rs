@@ -0,0 +1,76 @@
+extern crate logitech_led as led;
+
+use led::{Driver, DeviceType, Operation, RetryPolicy, Error};
+use led::mock::{MockBackend, Call};
+
+#[test]
+fn startup_sequence() {
+    let mock = MockBackend::new();
+    let driver = Driver::builder()
+        .backend(mock.clone())
+        .app_name("logitech-led test")
+        .target(DeviceType::PERKEY_RGB)
+        .save_restore(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(mock.calls(), vec![
+        Call::InitWithName("logitech-led test".to_owned()),
+        Call::SaveCurrentLighting,
+        Call::SetTargetDevice(DeviceType::PERKEY_RGB),
+    ]);
+
+    mock.clear_calls();
+    drop(driver);
+    assert_eq!(mock.calls(), vec![Call::RestoreLighting, Call::Shutdown]);
+}
+
+#[test]
+fn init_is_retried() {
+    let mock = MockBackend::new();
+    mock.fail_next(Operation::Init, 2);
+    Driver::builder()
+        .backend(mock.clone())
+        .retry(RetryPolicy::new(3))
+        .build()
+        .unwrap();
+    assert_eq!(mock.count(Operation::Init), 3);
+}
+
+#[test]
+fn rejects_invalid_combinations() {
+    let result = Driver::builder()
+        .backend(MockBackend::new())
+        .library_path("LogitechLed.dll")
+        .build();
+    match result {
+        Err(Error::InvalidConfig(_)) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    match Driver::builder().backend(MockBackend::new()).app_name("").build() {
+        Err(Error::InvalidConfig(_)) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    match Driver::builder().backend(MockBackend::new()).target(DeviceType::empty()).build() {
+        Err(Error::InvalidConfig(_)) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn white_balance_applies_to_percent() {
+    use led::Color;
+    use led::color::WhiteBalance;
+
+    let mock = MockBackend::new();
+    let mut driver = Driver::builder()
+        .backend(mock.clone())
+        .white_balance(WhiteBalance::new(1.0, 1.0, 0.5))
+        .build()
+        .unwrap();
+
+    driver.set_lighting(Color::WHITE).unwrap();
+    assert_eq!(mock.calls().last(), Some(&Call::SetLighting((100, 100, 74))));
+}
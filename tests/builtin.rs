@@ -0,0 +1,84 @@
+extern crate logitech_led as led;
+
+mod common;
+
+use common::{ms, close_color as close};
+use led::{Color, Direction, FloatFrame, Key};
+use led::effect::Effect;
+use led::effect::builtin::*;
+
+/// Render `effect` at `t` onto a frame filled with `below`.
+fn render<E: Effect>(effect: &mut E, t: u64, below: Color) -> FloatFrame {
+    let mut frame = FloatFrame::filled(below);
+    effect.render(ms(t), &mut frame);
+    frame
+}
+
+#[test]
+fn whole_keyboard() {
+    let frame = render(&mut Static::new(Color::RED), 0, Color::BLACK);
+    assert!(frame.iter().all(|(_, c)| c == Color::RED));
+
+    let mut breathing = Breathing { color: Color::WHITE, period: ms(1000), min: 0.2 };
+    assert_eq!(breathing.duration(), None);
+    assert!(close(render(&mut breathing, 0, Color::BLACK).get(Key::A), Color::WHITE * 0.2));
+    assert!(close(render(&mut breathing, 500, Color::BLACK).get(Key::G_1), Color::WHITE));
+    assert!(close(render(&mut breathing, 1000, Color::BLACK).get(Key::A), Color::WHITE * 0.2));
+
+    let mut cycle = SpectrumCycle { period: ms(3000), ..SpectrumCycle::new() };
+    assert!(close(render(&mut cycle, 0, Color::BLACK).get(Key::A), Color::RED));
+    assert!(close(render(&mut cycle, 1000, Color::BLACK).get(Key::G_LOGO), Color::LIME));
+    assert!(close(render(&mut cycle, 5000, Color::BLACK).get(Key::A), Color::BLUE));
+}
+
+#[test]
+fn moving_across() {
+    let mut wave = RainbowWave::new(Direction::Horizontal);
+    let frame = render(&mut wave, 0, Color::BLACK);
+    assert!(close(frame.get(Key::ESC), Color::RED));
+    assert_ne!(frame.get(Key::ESC), frame.get(Key::NUM_LOCK));
+    // Keys outside the bitmap are left alone.
+    assert_eq!(frame.get(Key::G_1), Color::BLACK);
+    // A full period later the wave is back where it started.
+    assert_eq!(render(&mut wave, 2000, Color::BLACK), frame);
+
+    let mut scroll = GradientScroll::new(led::Gradient::rainbow(), Direction::Horizontal);
+    let start = render(&mut scroll, 0, Color::BLACK);
+    assert_ne!(render(&mut scroll, 1000, Color::BLACK), start);
+    assert_eq!(render(&mut scroll, 5000, Color::BLACK), start);
+
+    let mut wheel = ColorWheel::new();
+    let frame = render(&mut wheel, 0, Color::BLACK);
+    assert_ne!(frame.get(Key::ESC), frame.get(Key::NUM_ENTER));
+    assert_eq!(render(&mut wheel, 4000, Color::BLACK), frame);
+}
+
+#[test]
+fn scanner_sweeps() {
+    let mut scanner = Scanner { color: Color::RED, width: 1.0, period: ms(2000) };
+    let left = render(&mut scanner, 0, Color::BLUE);
+    assert_eq!(left.get(Key::ESC), Color::RED);
+    assert_eq!(left.get(Key::NUM_LOCK), Color::BLUE);
+
+    let right = render(&mut scanner, 1000, Color::BLUE);
+    assert_eq!(right.get(Key::ESC), Color::BLUE);
+
+    // On its way back.
+    assert_eq!(render(&mut scanner, 2000, Color::BLUE), left);
+}
+
+#[test]
+fn starlight_is_repeatable() {
+    let mut stars = Starlight::new(Color::WHITE);
+    let a = render(&mut stars, 1234, Color::BLACK);
+    assert_eq!(render(&mut stars, 1234, Color::BLACK), a);
+
+    let lit = a.iter().filter(|&(_, c)| c != Color::BLACK).count();
+    assert!(lit > 0 && lit < a.iter().count() / 2, "{} keys lit", lit);
+
+    let mut other = Starlight { seed: 7, ..stars };
+    assert_ne!(render(&mut other, 1234, Color::BLACK), a);
+
+    let mut none = Starlight { density: 0.0, ..stars };
+    assert_eq!(render(&mut none, 1234, Color::GREEN), FloatFrame::filled(Color::GREEN));
+}
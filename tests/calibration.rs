@@ -0,0 +1,109 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Bitmap, BGRA, Color, Calibration, Key};
+use led::calibration::{Routine, Step, LUT_SIZE};
+use led::color::WhiteBalance;
+use led::mock::{MockBackend, Call};
+
+#[test]
+fn text_round_trip() {
+    let mut cal = Calibration::with_gamma(2.2);
+    cal.gain = [1.0, 0.9, 0.75];
+    cal.black = [0.0, 0.0, 0.02];
+    let mut lut = [[0; LUT_SIZE]; 3];
+    for c in lut.iter_mut() {
+        for (i, v) in c.iter_mut().enumerate() {
+            *v = (100 - i) as u8;
+        }
+    }
+    cal.lut = Some(lut);
+
+    assert_eq!(cal.to_string().parse::<Calibration>().unwrap(), cal);
+
+    let path = std::env::temp_dir().join(format!("logitech-led-{}.cal", std::process::id()));
+    cal.save(&path).unwrap();
+    assert_eq!(Calibration::load(&path).unwrap(), cal);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!("gamma = 1 2".parse::<Calibration>().is_err());
+    assert!("contrast = 1 1 1".parse::<Calibration>().is_err());
+}
+
+#[test]
+fn driver_applies_calibration() {
+    let mock = MockBackend::new();
+    let mut cal = Calibration::with_gamma(2.0);
+    cal.gain = [1.0, 1.0, 0.5];
+    let mut driver = Driver::builder().backend(mock.clone()).calibration(cal).build().unwrap();
+
+    driver.set_lighting(Color::new(0.5, 0.0, 0.5)).unwrap();
+    assert_eq!(mock.calls().last(), Some(&Call::SetLighting((25, 0, 13))));
+}
+
+/// The cell of `key` in the last bitmap sent to `mock`.
+fn sent_key(mock: &MockBackend, key: Key) -> BGRA {
+    let bytes = mock.calls().into_iter().rev().filter_map(|c| match c {
+        Call::SetLightingFromBitmap(b) => Some(b),
+        _ => None,
+    }).next().unwrap();
+    let (x, y) = led::bitmap_position(key).unwrap();
+    BGRA::from(Bitmap::from_bytes(&bytes).get_rgba(x, y))
+}
+
+#[test]
+fn bitmaps_are_corrected() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::builder().backend(mock.clone()).calibration(Calibration::with_gamma(2.0))
+        .build().unwrap();
+
+    let gray = Color::new(0.5, 0.5, 0.5);
+    let mut bitmap = Bitmap::filled(gray);
+    bitmap.set_rgba(0, 0, Color::WHITE.with_alpha(0.0));
+    driver.set_lighting_from_bitmap(&bitmap).unwrap();
+    assert_eq!(sent_key(&mock, Key::A), [64, 64, 64, 255]);
+    // The alpha is kept.
+    assert_eq!(sent_key(&mock, Key::ESC), [255, 255, 255, 0]);
+
+    // The same color sent per key gets the same correction.
+    driver.set_lighting_for_key(Key::A, gray).unwrap();
+    assert_eq!(mock.calls().last(), Some(&Call::SetLightingForKey(Key::A, (25, 25, 25))));
+
+    driver.set_calibration(None);
+    driver.set_white_balance(Some(WhiteBalance::new(1.0, 1.0, 0.0)));
+    driver.set_lighting_from_bitmap(&Bitmap::filled(Color::WHITE)).unwrap();
+    assert_eq!(sent_key(&mock, Key::A), [0, 255, 255, 255]);
+
+    // With a lookup table the keys go through percent, like every other color.
+    let mut cal = Calibration::new();
+    let mut lut = [[0; LUT_SIZE]; 3];
+    lut[0] = [50; LUT_SIZE];
+    cal.lut = Some(lut);
+    driver.set_white_balance(None);
+    driver.set_calibration(Some(cal));
+    driver.set_lighting_from_bitmap(&Bitmap::filled(Color::WHITE)).unwrap();
+    assert_eq!(sent_key(&mock, Key::A), [0, 0, 128, 255]);
+}
+
+#[test]
+fn guided_routine() {
+    let mut routine = Routine::new();
+    for _ in 0..3 {
+        routine.adjust(0.2);
+        routine.confirm();
+    }
+    assert_eq!(routine.step(), Step::Gain(0));
+    // Matching 50% of the light at 70% drive means a gamma of about 0.51,
+    // which brightens the midtones so that 50% is sent as 70%.
+    assert!((routine.calibration().gamma[2] - 0.515).abs() < 0.001);
+    assert!((routine.calibration().apply(Color::new(0.0, 0.0, 0.5)).b - 0.7).abs() < 0.001);
+
+    routine.confirm();
+    routine.adjust(-0.2);
+    routine.confirm();
+    routine.adjust(-0.3);
+    assert!(routine.pattern().get(0, 0).approx_eq(&Color::new(1.0, 0.8, 0.7), 1.0 / 255.0));
+    routine.confirm();
+
+    assert_eq!(routine.step(), Step::Done);
+    assert_eq!(routine.calibration().gain, [1.0, 0.8, 0.7]);
+}
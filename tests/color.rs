@@ -0,0 +1,100 @@
+extern crate logitech_led as led;
+
+use led::{Color, BlendMode};
+
+#[test]
+fn operators_saturate() {
+    let c = Color::new(0.6, 0.2, 0.0);
+    assert_eq!(c + c, Color::new(1.0, 0.4, 0.0));
+    assert_eq!(c - Color::WHITE, Color::BLACK);
+    assert_eq!(c * 0.5, Color::new(0.3, 0.1, 0.0));
+    assert_eq!(c * 10.0, Color::new(1.0, 1.0, 0.0));
+    assert_eq!(Color::YELLOW * Color::CYAN, Color::LIME);
+}
+
+#[test]
+fn lerp_and_equality() {
+    assert_eq!(Color::RED.lerp(Color::BLUE, 0.0), Color::RED);
+    assert_eq!(Color::RED.lerp(Color::BLUE, 0.25), Color::new(0.75, 0.0, 0.25));
+    assert_eq!(Color::RED.lerp(Color::BLUE, 2.0), Color::BLUE);
+    assert!(Color::new(0.5, 0.5, 0.5) != Color::new(0.5, 0.5, 0.51));
+    assert!(Color::new(0.5, 0.5, 0.5).approx_eq(&Color::new(0.5, 0.5, 0.51), 0.02));
+}
+
+#[test]
+fn blend_modes() {
+    let base = Color::new(0.25, 0.75, 0.5);
+    let top = Color::new(0.5, 0.5, 0.5);
+    assert_eq!(base.blend(top, BlendMode::Normal), top);
+    assert_eq!(base.blend(top, BlendMode::Overlay), Color::new(0.25, 0.75, 0.5));
+    assert_eq!(base.blend(top, BlendMode::Add), Color::new(0.75, 1.0, 1.0));
+    assert_eq!(base.blend(top, BlendMode::Difference), Color::new(0.25, 0.25, 0.0));
+    assert_eq!(BlendMode::Multiply.apply_with_opacity(base, Color::BLACK, 0.5), base * 0.5);
+}
+
+#[test]
+fn parse_formats() {
+    let orange = Color::new(1.0, 0.5, 0.0);
+    for s in &["#ff8000", "#FF8000", " rgb(255, 127.5, 0) ", "rgb(100% 50% 0%)",
+               "hsl(30, 100%, 50%)", "hsv(30deg, 100%, 100%)", "hsv(390, 100, 100)"] {
+        assert!(s.parse::<Color>().unwrap().approx_eq(&orange, 0.003), "{}", s);
+    }
+    assert_eq!("#0f0".parse::<Color>().unwrap(), Color::LIME);
+    for &(name, color) in &[("black", Color::BLACK), ("white", Color::WHITE), ("red", Color::RED),
+        ("lime", Color::LIME), ("green", Color::GREEN), ("blue", Color::BLUE), ("yellow", Color::YELLOW),
+        ("cyan", Color::CYAN), ("magenta", Color::MAGENTA), ("orange", Color::ORANGE), ("purple", Color::PURPLE)] {
+        assert_eq!(name.parse::<Color>().unwrap(), color, "{}", name);
+    }
+    assert_eq!("DarkOrange".parse::<Color>().unwrap().to_hex(), "#ff8c00");
+    assert_eq!(Color::new(0.2, 0.4, 0.6).to_string().parse::<Color>().unwrap().to_hex(), "#336699");
+}
+
+#[test]
+fn parse_errors() {
+    use led::color::ParseColorError;
+
+    assert_eq!("".parse::<Color>(), Err(ParseColorError::Empty));
+    assert_eq!("#12345".parse::<Color>(), Err(ParseColorError::InvalidHex("#12345".to_owned())));
+    assert_eq!("#gg0000".parse::<Color>(), Err(ParseColorError::InvalidHex("#gg0000".to_owned())));
+    assert_eq!("blurple".parse::<Color>(), Err(ParseColorError::UnknownName("blurple".to_owned())));
+    assert_eq!("rgb(1, x, 3)".parse::<Color>(), Err(ParseColorError::InvalidArgument("x".to_owned())));
+    assert_eq!("rgb(1, inf, 3)".parse::<Color>(), Err(ParseColorError::InvalidArgument("inf".to_owned())));
+    assert_eq!("rgb(300, -5, 150%)".parse::<Color>(), Ok(Color::MAGENTA));
+    match "hsl(1, 2)".parse::<Color>() {
+        Err(ParseColorError::ArgumentCount { expected: 3, found: 2, .. }) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn color_space_round_trips() {
+    for i in 0..64 {
+        let c = Color::from_hsv(i as f32 * 37.0, (i % 5) as f32 / 4.0, (i % 7) as f32 / 6.0);
+        let (h, s, v) = c.to_hsv();
+        assert!(Color::from_hsv(h, s, v).approx_eq(&c, 1e-5), "hsv {:?}", c);
+        let (h, s, l) = c.to_hsl();
+        assert!(Color::from_hsl(h, s, l).approx_eq(&c, 1e-5), "hsl {:?}", c);
+        assert!(Color::from_linear(c.to_linear()).approx_eq(&c, 1e-5), "linear {:?}", c);
+        assert!(Color::from_xyz(c.to_xyz()).approx_eq(&c, 1e-4), "xyz {:?}", c);
+        assert!(Color::from_oklch(c.to_oklch()).approx_eq(&c, 1e-4), "oklch {:?}", c);
+    }
+
+    let white = Color::WHITE.to_xyz();
+    assert!((white.x - 0.9505).abs() < 1e-3 && (white.y - 1.0).abs() < 1e-3 && (white.z - 1.089).abs() < 1e-3);
+    let white = Color::WHITE.to_oklab();
+    assert!((white.l - 1.0).abs() < 1e-3 && white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+}
+
+#[test]
+fn color_temperature() {
+    use led::color::WhitePoint;
+
+    let blue = |k: f32| { let c = Color::from_kelvin(k); c.b / c.r };
+    assert!(blue(1800.0) < blue(2700.0) && blue(2700.0) < blue(5000.0) && blue(5000.0) < blue(9000.0));
+    assert!(Color::from_kelvin(6500.0).approx_eq(&Color::WHITE, 0.03));
+
+    let c = Color::new(0.2, 0.6, 0.9);
+    assert!(c.shift_toward(WhitePoint::A, 0.0).approx_eq(&c, 1e-4));
+    let warm = c.shift_toward(WhitePoint::A, 1.0);
+    assert!(warm.r > c.r && warm.b < c.b);
+}
@@ -0,0 +1,26 @@
+//! Helpers shared by the integration tests, each test crate uses some of them.
+
+#![allow(dead_code)]
+
+use led::{Color, FloatFrame, Key};
+use led::effect::{self, Effect};
+use std::time::Duration;
+
+pub fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// Red of `Key::A` rising from 0 to 1 over a second.
+pub fn ramp() -> impl Effect {
+    effect::from_fn(|t: Duration, frame: &mut FloatFrame| {
+        frame.set(Key::A, Color::new(t.as_secs_f32(), 0.0, 0.0));
+    }).with_duration(Duration::from_secs(1))
+}
+
+pub fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+pub fn close_color(a: Color, b: Color) -> bool {
+    a.approx_eq(&b, 1e-3)
+}
@@ -0,0 +1,95 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Bitmap, BlendMode, Color, FloatFrame, Key, KeySet, Operation};
+use led::compositor::{Compositor, Layer};
+use led::mock::MockBackend;
+
+#[test]
+fn layers_are_blended_in_order() {
+    let mut compositor = Compositor::new();
+    assert_eq!(compositor.flatten(), &FloatFrame::new());
+
+    let base = compositor.push(Layer::frame(FloatFrame::filled(Color::BLUE)));
+    let mut half = Layer::bitmap(Bitmap::filled(Color::RED));
+    half.set_opacity(0.5);
+    let half = compositor.push(half);
+
+    let c = compositor.flatten().get(Key::A);
+    assert!(c.approx_eq(&Color::new(0.5, 0.0, 0.5), 1e-5), "{:?}", c);
+    // The red bitmap doesn't cover the keys outside it.
+    assert_eq!(compositor.flatten().get(Key::G_1), Color::BLUE);
+
+    compositor.move_to(half, 0);
+    assert_eq!(compositor.index(base), Some(1));
+    assert_eq!(compositor.flatten().get(Key::A), Color::BLUE);
+
+    compositor.remove(base);
+    assert_eq!(compositor.len(), 1);
+    assert_eq!(compositor.flatten().get(Key::A), Color::new(0.5, 0.0, 0.0));
+}
+
+#[test]
+fn masks_modes_and_visibility() {
+    let mut compositor = Compositor::new();
+    compositor.push(Layer::frame(FloatFrame::filled(Color::new(0.5, 0.5, 0.5))));
+
+    let mut tint = Layer::frame(FloatFrame::filled(Color::RED));
+    tint.set_mode(BlendMode::Multiply);
+    tint.set_mask(Some(KeySet::g_keys()));
+    let tint = compositor.push(tint);
+
+    assert_eq!(compositor.flatten().get(Key::G_3), Color::new(0.5, 0.0, 0.0));
+    assert_eq!(compositor.flatten().get(Key::G_LOGO), Color::new(0.5, 0.5, 0.5));
+
+    compositor.layer_mut(tint).unwrap().set_visible(false);
+    assert_eq!(compositor.flatten().get(Key::G_3), Color::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn renders_only_when_dirty() {
+    let mut compositor = Compositor::new();
+    let overlay = compositor.push(Layer::bitmap(Bitmap::transparent()));
+    assert!(compositor.is_dirty());
+    compositor.flatten();
+    assert!(!compositor.is_dirty());
+
+    // Reading doesn't dirty, neither does changing a hidden layer.
+    assert!(compositor.layer(overlay).unwrap().is_visible());
+    assert!(!compositor.is_dirty());
+    compositor.layer_mut(overlay).unwrap().set_visible(false);
+    assert!(compositor.is_dirty());
+    compositor.flatten();
+    compositor.layer_mut(overlay).unwrap().set_opacity(0.5);
+    assert!(!compositor.is_dirty());
+
+    compositor.layer_mut(overlay).unwrap().set_visible(true);
+    compositor.flatten();
+    // Asking for the wrong kind of content doesn't dirty either.
+    assert!(compositor.layer_mut(overlay).unwrap().frame_mut().is_none());
+    assert!(!compositor.is_dirty());
+    compositor.layer_mut(overlay).unwrap().bitmap_mut().unwrap().set_key(Key::W, Color::WHITE);
+    assert!(compositor.layer(overlay).unwrap().is_dirty());
+    assert_eq!(compositor.flatten().get(Key::W), Color::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn sends_only_changes() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    let mut compositor = Compositor::new();
+    let layer = compositor.push(Layer::bitmap(Bitmap::filled(Color::GREEN)));
+
+    assert!(compositor.send(&mut driver).unwrap());
+    assert!(!compositor.send(&mut driver).unwrap());
+    assert_eq!(mock.count(Operation::SetLightingFromBitmap), 1);
+
+    compositor.layer_mut(layer).unwrap().set_opacity(0.25);
+    assert!(compositor.send(&mut driver).unwrap());
+    assert_eq!(mock.count(Operation::SetLightingFromBitmap), 2);
+
+    mock.fail_always(Operation::SetLightingFromBitmap);
+    compositor.layer_mut(layer).unwrap().set_opacity(1.0);
+    assert!(compositor.send(&mut driver).is_err());
+    mock.clear_failures();
+    assert!(compositor.send(&mut driver).unwrap());
+}
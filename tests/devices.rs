@@ -0,0 +1,67 @@
+extern crate logitech_led as led;
+
+use led::{Color, DeviceType};
+use led::devices::{self, Brightness, ColorDepth, DeviceModel};
+
+const DEVICES_MD: &str = include_str!("../DEVICES.md");
+
+/// The section of DEVICES.md whose heading links `model`.
+fn section(model: DeviceModel) -> &'static str {
+    let link = format!("[{}](", model.name());
+    DEVICES_MD.split("\n### ").skip(1)
+        .find(|s| s.lines().next().unwrap().contains(&link))
+        .unwrap_or_else(|| panic!("{} is not in DEVICES.md", model))
+}
+
+#[test]
+fn table_matches_devices_md() {
+    for &model in DeviceModel::ALL.iter() {
+        let text = section(model);
+        let caps = model.capabilities();
+
+        assert_eq!(caps.per_key, text.contains("Single key"), "{}", model);
+        assert_eq!(caps.requires_host_mode, text.contains("Host mode"), "{}", model);
+        assert_eq!(caps.color_depth == ColorDepth::RedBlue, text.contains("not G(reen)"), "{}", model);
+        assert_eq!(caps.brightness == Brightness::ChannelOnOff, text.contains("below 50"), "{}", model);
+        assert_eq!(caps.brightness == Brightness::MaxChannelLevels, text.contains("3 levels"), "{}", model);
+        assert_eq!(caps.brightness == Brightness::MaxChannel,
+            text.contains("Highest value for R, G or B") || text.contains("highest value for R,G,B"), "{}", model);
+        assert_eq!(caps.color_depth == ColorDepth::Monochrome,
+            text.contains("Single color only") || text.contains("Monochrome"), "{}", model);
+        // DEVICES.md doesn't list zone counts.
+        assert_eq!(caps.zones, if caps.zone_target.is_some() { None } else { Some(0) }, "{}", model);
+    }
+}
+
+#[test]
+fn documented_limitations() {
+    let g110 = DeviceModel::G110.capabilities();
+    assert_eq!(g110.color_depth, ColorDepth::RedBlue);
+    assert_eq!(g110.simulate(Color::new(0.5, 1.0, 0.25)), Color::new(0.5, 0.0, 0.25));
+
+    let g300 = DeviceModel::G300.capabilities();
+    assert_eq!(g300.simulate(Color::new(0.49, 0.51, 1.0)), Color::new(0.0, 1.0, 1.0));
+
+    for &model in [DeviceModel::G11, DeviceModel::G15v1, DeviceModel::G15v2].iter() {
+        let caps = model.capabilities();
+        assert_eq!(caps.brightness, Brightness::MaxChannelLevels);
+        assert_eq!(caps.simulate(Color::new(0.3, 0.0, 0.0)), Color::BLACK);
+        assert_eq!(caps.simulate(Color::new(0.0, 0.5, 0.0)), Color::new(0.5, 0.5, 0.5));
+        assert_eq!(caps.simulate(Color::new(0.0, 0.0, 0.7)), Color::WHITE);
+    }
+
+    assert!(DeviceModel::G600.capabilities().requires_host_mode);
+    assert!(!DeviceModel::G910.capabilities().requires_host_mode);
+}
+
+#[test]
+fn queries() {
+    assert_eq!(devices::per_key_models(), vec![DeviceModel::G910, DeviceModel::G810, DeviceModel::G610]);
+    assert_eq!(devices::zoned_models(), vec![DeviceModel::G633, DeviceModel::G933, DeviceModel::G900]);
+    assert_eq!(DeviceModel::G933.capabilities().zone_list(), None);
+    assert_eq!(DeviceModel::G910.capabilities().zone_list(), Some(Vec::new()));
+
+    let monochrome = devices::models_supporting(DeviceType::MONOCHROME);
+    assert!(monochrome.contains(&DeviceModel::G105));
+    assert!(!monochrome.contains(&DeviceModel::G510));
+}
@@ -0,0 +1,59 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Color, Key, DitherMode};
+use led::dither::Dither;
+use led::mock::{MockBackend, Call};
+
+const FRAMES: usize = 1000;
+
+/// Mean of the percentages sent for `key`, as a color.
+fn mean_for_key(mock: &MockBackend, key: Key) -> [f32; 3] {
+    let sent: Vec<_> = mock.calls().into_iter().filter_map(|c| match c {
+        Call::SetLightingForKey(k, p) if k == key => Some(p),
+        _ => None,
+    }).collect();
+    let n = sent.len() as f32;
+    let sum = sent.iter().fold([0.0; 3], |s, p| [s[0] + p.0 as f32, s[1] + p.1 as f32, s[2] + p.2 as f32]);
+    [sum[0] / n / 100.0, sum[1] / n / 100.0, sum[2] / n / 100.0]
+}
+
+#[test]
+fn temporal_mean_matches_requested_color() {
+    let requested = Color::new(0.004, 0.3333, 0.9999);
+    let mock = MockBackend::new();
+    let mut driver = Driver::builder().backend(mock.clone()).dither(DitherMode::Temporal).build().unwrap();
+
+    for _ in 0..FRAMES {
+        driver.set_lighting_for_key(Key::W, requested).unwrap();
+    }
+    let mean = mean_for_key(&mock, Key::W);
+    let bound = 1.0 / (100.0 * FRAMES as f32) + 1e-5;
+    assert!((mean[0] - requested.r).abs() < bound, "{:?}", mean);
+    assert!((mean[1] - requested.g).abs() < bound, "{:?}", mean);
+    assert!((mean[2] - requested.b).abs() < bound, "{:?}", mean);
+}
+
+#[test]
+fn without_dither_every_frame_is_the_same() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+
+    for _ in 0..10 {
+        driver.set_lighting_for_key(Key::A, Color::new(0.004, 0.0, 0.0)).unwrap();
+    }
+    assert_eq!(mean_for_key(&mock, Key::A), [0.0; 3]);
+}
+
+#[test]
+fn outputs_are_dithered_separately() {
+    let mut dither = Dither::new(DitherMode::Temporal);
+    let c = Color::new(0.004, 0.0, 0.0);
+
+    assert_eq!(dither.quantize(1, c), (0, 0, 0));
+    assert_eq!(dither.quantize(2, c), (0, 0, 0));
+    assert_eq!(dither.quantize(1, c), (1, 0, 0));
+    assert_eq!(dither.quantize(2, c), (1, 0, 0));
+
+    dither.set_mode(DitherMode::Temporal);
+    assert_eq!(dither.quantize(1, c), (0, 0, 0));
+}
@@ -0,0 +1,107 @@
+extern crate logitech_led as led;
+
+mod common;
+
+use common::{ms, ramp, close};
+use led::{BlendMode, Color, FloatFrame, Key, KeySet};
+use led::effect::{self, Effect};
+use std::time::Duration;
+
+/// Fills every key with `color`, forever.
+fn solid(color: Color) -> impl Effect {
+    effect::from_fn(move |_: Duration, frame: &mut FloatFrame| frame.fill(color))
+}
+
+/// Red of `Key::A` after rendering `effect` at `t` onto black.
+fn red_at<E: Effect>(effect: &mut E, t: u64) -> f32 {
+    let mut frame = FloatFrame::new();
+    effect.render(ms(t), &mut frame);
+    frame.get(Key::A).r
+}
+
+#[test]
+fn time_adapters() {
+    let mut looped = ramp().loop_n(3);
+    assert_eq!(looped.duration(), Some(ms(3000)));
+    assert!(close(red_at(&mut looped, 250), 0.25));
+    assert!(close(red_at(&mut looped, 1250), 0.25));
+    assert!(close(red_at(&mut looped, 5000), 1.0));
+
+    let mut reversed = ramp().reverse();
+    assert!(close(red_at(&mut reversed, 0), 1.0));
+    assert!(close(red_at(&mut reversed, 750), 0.25));
+
+    let mut fast = ramp().speed(2.0);
+    assert_eq!(fast.duration(), Some(ms(500)));
+    assert!(close(red_at(&mut fast, 250), 0.5));
+    assert!(close(red_at(&mut fast, 900), 1.0));
+
+    let mut delayed = ramp().delay(ms(500));
+    assert_eq!(delayed.duration(), Some(ms(1500)));
+    assert_eq!(red_at(&mut delayed, 100), 0.0);
+    assert!(close(red_at(&mut delayed, 750), 0.25));
+
+    let mut bounce = ramp().ping_pong();
+    assert_eq!(bounce.duration(), Some(ms(2000)));
+    assert!(close(red_at(&mut bounce, 500), 0.5));
+    assert!(close(red_at(&mut bounce, 1750), 0.25));
+    assert!(close(red_at(&mut bounce, 3000), 0.0));
+
+    // Without an end there is nothing to loop or reverse.
+    assert_eq!(solid(Color::RED).reverse().loop_n(2).duration(), None);
+}
+
+#[test]
+fn sequence_plays_in_turn() {
+    let mut seq = effect::sequence(vec![
+        Box::new(ramp()),
+        Box::new(ramp().reverse()),
+    ]);
+    assert_eq!(seq.duration(), Some(ms(2000)));
+    assert!(close(red_at(&mut seq, 500), 0.5));
+    assert!(close(red_at(&mut seq, 1250), 0.75));
+    assert!(close(red_at(&mut seq, 9000), 0.0));
+
+    let forever = effect::sequence(vec![Box::new(solid(Color::RED)), Box::new(ramp())]);
+    assert_eq!(forever.duration(), None);
+}
+
+#[test]
+fn crossfade_overlaps() {
+    let mut fade = effect::crossfade(ramp(), effect::from_fn(|_: Duration, frame: &mut FloatFrame| {
+        frame.fill(Color::BLUE)
+    }).with_duration(ms(1000)), ms(500));
+    assert_eq!(fade.duration(), Some(ms(1500)));
+
+    let mut frame = FloatFrame::new();
+    fade.render(ms(250), &mut frame);
+    assert_eq!(frame.get(Key::A), Color::new(0.25, 0.0, 0.0));
+
+    fade.render(ms(750), &mut frame);
+    let l = frame.linear(Key::A);
+    assert!(close(l.r, Color::new(0.75, 0.0, 0.0).to_linear().r * 0.5));
+    assert!(close(l.b, 0.5));
+
+    fade.render(ms(1200), &mut frame);
+    assert_eq!(frame.get(Key::A), Color::BLUE);
+}
+
+#[test]
+fn keys_and_colors() {
+    let mut masked = solid(Color::RED).mask(KeySet::g_keys());
+    let mut frame = FloatFrame::filled(Color::GREEN);
+    masked.render(ms(0), &mut frame);
+    assert_eq!(frame.get(Key::G_1), Color::RED);
+    assert_eq!(frame.get(Key::A), Color::GREEN);
+
+    let mut dimmed = solid(Color::WHITE).map_color(|c| c * 0.5);
+    dimmed.render(ms(0), &mut frame);
+    assert_eq!(frame.get(Key::A), Color::new(0.5, 0.5, 0.5));
+
+    let mut multiplied = effect::blend(solid(Color::new(0.5, 0.5, 0.5)), ramp(), BlendMode::Multiply);
+    assert_eq!(multiplied.duration(), None);
+    multiplied.render(ms(500), &mut frame);
+    assert_eq!(frame.get(Key::A), Color::new(0.25, 0.0, 0.0));
+    multiplied.render(ms(4000), &mut frame);
+    assert_eq!(frame.get(Key::A), Color::new(0.5, 0.0, 0.0));
+}
@@ -0,0 +1,47 @@
+extern crate logitech_led as led;
+
+use led::{Color, Error, ErrorKind, Key, Operation};
+use std::error::Error as StdError;
+use std::io;
+
+#[test]
+fn display_includes_call_context() {
+    let err = Error::SetLightingForKeyWithKeyName { key: Key::W, color: Color::RED };
+    let msg = err.to_string();
+    assert!(msg.starts_with("LogiLedSetLightingForKeyWithKeyName failed for key W"), "{}", msg);
+
+    let err = Error::GetConfigOptionNumber { path: "a/b".to_owned() };
+    assert_eq!(err.to_string(), "LogiGetConfigOptionNumber failed for config option \"a/b\"");
+
+    assert_eq!(Error::Init.to_string(), "LogiLedInit failed");
+    assert_eq!(Error::InvalidConfig("bad").to_string(), "invalid driver configuration: bad");
+}
+
+#[test]
+fn source_is_the_underlying_error() {
+    let err = Error::LoadLibrary(io::Error::new(io::ErrorKind::NotFound, "no dll"));
+    assert!(err.to_string().ends_with("no dll"));
+    assert_eq!(err.source().unwrap().to_string(), "no dll");
+
+    assert!(Error::StopEffects.source().is_none());
+    assert!(Error::NullCharacter.source().is_none());
+}
+
+#[test]
+fn kind() {
+    assert_eq!(Error::SetLighting { color: Color::RED }.kind(), ErrorKind::Retryable);
+    assert_eq!(Error::SetLightingFromBitmap.kind(), ErrorKind::Retryable);
+    assert!(Error::StopEffects.is_retryable());
+
+    for err in [
+        Error::Init,
+        Error::InitWithName { name: "app".to_owned() },
+        Error::Shutdown,
+        Error::Unsupported(Operation::GetSdkVersion),
+        Error::NullCharacter,
+        Error::InvalidConfig("bad"),
+        Error::LoadLibrary(io::Error::new(io::ErrorKind::NotFound, "no dll")),
+    ] {
+        assert_eq!(err.kind(), ErrorKind::Permanent, "{:?}", err);
+    }
+}
@@ -0,0 +1,59 @@
+extern crate logitech_led as led;
+
+use led::{Driver, DeviceType, Reducer, Color, Key, BITMAP_SIZE, BITMAP_BYTES_PER_KEY, bitmap_index};
+use led::mock::{MockBackend, Call};
+
+fn set_key(bitmap: &mut [u8], key: Key, bgra: [u8; 4]) {
+    let o = bitmap_index(key).unwrap() * BITMAP_BYTES_PER_KEY;
+    bitmap[o..o + 4].copy_from_slice(&bgra);
+}
+
+#[test]
+fn bitmap_is_also_sent_to_whole_device_targets() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::builder().backend(mock.clone()).fallback(Reducer::MaxBrightness).build().unwrap();
+    mock.clear_calls();
+
+    let mut bitmap = vec![0u8; BITMAP_SIZE];
+    set_key(&mut bitmap, Key::W, [0, 0, 255, 255]);
+    driver.set_lighting_from_bitmap(&bitmap).unwrap();
+
+    assert_eq!(mock.calls(), vec![
+        Call::SetLightingFromBitmap(bitmap.clone()),
+        Call::SetTargetDevice(DeviceType::RGB | DeviceType::MONOCHROME),
+        Call::SetLighting((100, 0, 0)),
+        Call::SetTargetDevice(DeviceType::ALL),
+    ]);
+    assert_eq!(driver.lighting_state().target, DeviceType::ALL);
+    assert_eq!(driver.lighting_state().bitmap, Some(bitmap));
+}
+
+#[test]
+fn no_fallback_outside_target() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::builder().backend(mock.clone()).target(DeviceType::PERKEY_RGB)
+        .fallback(Reducer::Average).build().unwrap();
+    mock.clear_calls();
+
+    driver.set_lighting_from_bitmap(&vec![0u8; BITMAP_SIZE]).unwrap();
+    assert_eq!(mock.calls().len(), 1);
+}
+
+#[test]
+fn reducers() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let dim_blue = Color::new(0.0, 0.0, 0.5);
+    let colors = [red, red, dim_blue, Color::new(1.0, 1.0, 1.0)];
+
+    let avg = Reducer::Average.reduce(&colors);
+    assert!((avg.r - 0.75).abs() < 1e-6 && (avg.b - 0.375).abs() < 1e-6);
+
+    let dominant = Reducer::Dominant.reduce(&colors);
+    assert!(dominant.r == 1.0 && dominant.g == 0.0 && dominant.b == 0.0);
+
+    let max = Reducer::MaxBrightness.reduce(&colors[2..]);
+    assert!(max.r == 1.0 && max.g == 1.0 && max.b == 1.0);
+
+    let first = Reducer::Custom(|c| c[0]).reduce(&colors[2..]);
+    assert_eq!(first.b, 0.5);
+}
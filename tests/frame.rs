@@ -0,0 +1,73 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Bitmap, BGRA, Calibration, Color, FloatFrame, Key, KeySet, Operation};
+use led::mock::{MockBackend, Call};
+
+fn bitmaps(mock: &MockBackend) -> Vec<Bitmap> {
+    mock.calls().into_iter().filter_map(|c| match c {
+        Call::SetLightingFromBitmap(b) => Some(Bitmap::from_bytes(&b)),
+        _ => None,
+    }).collect()
+}
+
+#[test]
+fn frame_keys() {
+    let mut frame = FloatFrame::new();
+    assert_eq!(frame, FloatFrame::filled(Color::BLACK));
+
+    frame.set(Key::G_LOGO, Color::RED);
+    frame.fill_keys(KeySet::g_keys(), Color::new(0.25, 0.5, 0.75));
+    assert_eq!(frame.get(Key::G_LOGO), Color::RED);
+    assert_eq!(frame.get(Key::G_9), Color::new(0.25, 0.5, 0.75));
+    assert_eq!(frame.get(Key::A), Color::BLACK);
+    assert_eq!(frame.linear(Key::G_LOGO).r, 1.0);
+    assert_eq!(frame.iter().count(), led::ALL_KEYS.len());
+
+    let mut bitmap = Bitmap::new();
+    bitmap.set_key(Key::Q, Color::GREEN);
+    let frame = FloatFrame::from_bitmap(&bitmap);
+    assert_eq!(frame.get(Key::Q), Color::GREEN);
+    assert_eq!(frame.to_bitmap(), bitmap);
+}
+
+#[test]
+fn frame_is_sent_as_bitmap_and_keys() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    mock.clear_calls();
+
+    let mut frame = FloatFrame::filled(Color::BLUE);
+    frame.set(Key::G_1, Color::RED);
+    driver.set_lighting_from_frame(&frame).unwrap();
+
+    let sent = bitmaps(&mock);
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].key(Key::ESC), Some(Color::BLUE));
+    assert_eq!(mock.count(Operation::SetLightingForKeyWithKeyName), KeySet::all().len() - KeySet::bitmap().len());
+    assert!(mock.calls().contains(&Call::SetLightingForKey(Key::G_1, (100, 0, 0))));
+    assert!(mock.calls().contains(&Call::SetLightingForKey(Key::G_LOGO, (0, 0, 100))));
+    assert_eq!(driver.lighting_state().keys.get(&Key::G_1), Some(&(100, 0, 0)));
+
+    // Keys outside the bitmap are only sent when they change.
+    mock.clear_calls();
+    frame.set(Key::G_2, Color::LIME);
+    driver.set_lighting_from_frame(&frame).unwrap();
+    assert_eq!(mock.count(Operation::SetLightingFromBitmap), 1);
+    assert_eq!(mock.calls().into_iter().filter(|c| c.operation() == Operation::SetLightingForKeyWithKeyName)
+        .collect::<Vec<_>>(), vec![Call::SetLightingForKey(Key::G_2, (0, 100, 0))]);
+}
+
+#[test]
+fn frame_goes_through_calibration() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::builder()
+        .backend(mock.clone())
+        .calibration(Calibration::with_gamma(2.0))
+        .build()
+        .unwrap();
+
+    driver.set_lighting_from_frame(&FloatFrame::filled(Color::new(0.5, 0.5, 0.5))).unwrap();
+    let (x, y) = led::bitmap_position(Key::A).unwrap();
+    assert_eq!(BGRA::from(bitmaps(&mock)[0].get(x, y)), [64, 64, 64, 255]);
+    assert!(mock.calls().contains(&Call::SetLightingForKey(Key::G_BADGE, (25, 25, 25))));
+}
@@ -0,0 +1,48 @@
+extern crate logitech_led as led;
+
+mod common;
+
+use common::close_color as close;
+use led::{Bitmap, Color, Direction, Gradient, Key, BITMAP_WIDTH, BITMAP_HEIGHT};
+use led::color::{Easing, Interpolation};
+
+#[test]
+fn sampling() {
+    let g = Gradient::new(&[(1.0, Color::BLUE), (0.0, Color::RED), (0.5, Color::LIME)]);
+    assert_eq!(g.stops()[1].1, Color::LIME);
+    assert_eq!(g.sample(-1.0), Color::RED);
+    assert_eq!(g.sample(0.25), Color::new(0.5, 0.5, 0.0));
+    assert_eq!(g.sample(0.75), Color::new(0.0, 0.5, 0.5));
+    assert_eq!(Gradient::default().sample(0.5), Color::BLACK);
+
+    // Shortest way from magenta to red goes through hue 330, not green.
+    let hsv = Gradient::even(&[Color::MAGENTA, Color::RED]).interpolation(Interpolation::Hsv);
+    assert_eq!(hsv.sample(0.5), Color::new(1.0, 0.0, 0.5));
+
+    let stepped = Gradient::even(&[Color::BLACK, Color::WHITE]).easing(Easing::Steps(2));
+    assert_eq!(stepped.samples(4), vec![Color::BLACK, Color::BLACK, Color::WHITE, Color::WHITE]);
+}
+
+#[test]
+fn painting() {
+    let g = Gradient::even(&[Color::BLACK, Color::WHITE]);
+    let mut bitmap = Bitmap::new();
+
+    bitmap.paint_gradient(&g, Direction::Horizontal);
+    assert_eq!(bitmap.get(0, 3), Color::BLACK);
+    assert!(close(bitmap.get(BITMAP_WIDTH - 1, 0), Color::WHITE));
+
+    bitmap.paint_gradient(&g, Direction::Angle(90.0));
+    assert_eq!(bitmap.get(7, 0), Color::BLACK);
+    assert!(close(bitmap.get(7, BITMAP_HEIGHT - 1), Color::WHITE));
+
+    bitmap.paint_gradient(&g, Direction::Radial { x: 0.0, y: 0.0 });
+    assert_eq!(bitmap.get(0, 0), Color::BLACK);
+    assert!(close(bitmap.get(BITMAP_WIDTH - 1, BITMAP_HEIGHT - 1), Color::WHITE));
+
+    assert!(bitmap.set_key(Key::ESC, Color::RED));
+    assert!(!bitmap.set_key(Key::G_1, Color::RED));
+    assert_eq!(bitmap.get(0, 0), Color::RED);
+    assert_eq!(bitmap.key(Key::ESC), Some(Color::RED));
+    assert_eq!(&bitmap[..4], &[0, 0, 255, 255]);
+}
@@ -0,0 +1,55 @@
+extern crate logitech_led as led;
+
+mod common;
+
+use common::close;
+use led::Color;
+use led::color::monochrome;
+
+#[test]
+fn level_is_highest_channel() {
+    assert_eq!(monochrome::level(Color::new(0.2, 0.7, 0.4)), 0.7);
+    assert_eq!(monochrome::level(Color::BLACK), 0.0);
+    assert_eq!(monochrome::preview_monochrome(Color::new(0.2, 0.0, 0.6)), Color::new(0.6, 0.6, 0.6));
+}
+
+#[test]
+fn analyze_sequences() {
+    let v = monochrome::analyze(vec![Color::new(0.2, 0.0, 0.0), Color::new(0.0, 0.9, 0.0), Color::new(0.0, 0.0, 0.5)]);
+    assert_eq!(v.samples, 3);
+    assert_eq!(v.min, 0.2);
+    assert_eq!(v.max, 0.9);
+    assert!(close(v.max_step, 0.7));
+    assert!(v.is_visible());
+    assert!(!v.is_visible_with(0.8));
+
+    let empty = monochrome::analyze(Vec::new());
+    assert_eq!((empty.samples, empty.min, empty.max), (0, 0.0, 0.0));
+    assert!(!empty.is_visible());
+
+    // A single color never changes, however bright.
+    assert!(!monochrome::analyze(vec![Color::WHITE]).is_visible());
+
+    let fade = monochrome::analyze_fn(|t| Color::new(t, 0.0, 0.0), 11);
+    assert_eq!(fade.samples, 11);
+    assert!(close(fade.range(), 1.0));
+    assert!(close(fade.max_step, 0.1));
+}
+
+#[test]
+fn levels_keep_hue() {
+    let c = monochrome::with_level(Color::new(1.0, 0.5, 0.0), 0.5);
+    assert!(c.approx_eq(&Color::new(0.5, 0.25, 0.0), 1e-6));
+    assert_eq!(monochrome::with_level(Color::BLACK, 0.3), Color::new(0.3, 0.3, 0.3));
+
+    let red = Color::RED;
+    assert_eq!(monochrome::modulate_at(red, 0.0, 0.8), red);
+    assert!(close(monochrome::level(monochrome::modulate_at(red, 0.5, 0.8)), 0.2));
+    assert_eq!(monochrome::modulate_at(red, 0.5, 0.0), red);
+
+    let modulated = monochrome::modulate(&[red; 4], 1.0);
+    assert_eq!(modulated.len(), 4);
+    assert_eq!(modulated[0], red);
+    assert!(close(monochrome::level(modulated[2]), 0.0));
+    assert!(modulated.iter().all(|c| c.g == 0.0 && c.b == 0.0));
+}
@@ -0,0 +1,39 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Operation, Error};
+use led::mock::{MockBackend, Call, MOCK_SDK_VERSION};
+
+#[test]
+fn probe_shuts_down_again() {
+    let mock = MockBackend::new();
+    mock.unsupport(Operation::SetLightingForTargetZone);
+
+    let probe = Driver::builder().backend(mock.clone()).probe();
+    assert!(probe.is_supported());
+    assert_eq!(probe.sdk_version, Some(MOCK_SDK_VERSION));
+    assert!(probe.supports(Operation::SetLighting));
+    assert!(!probe.supports(Operation::SetLightingForTargetZone));
+    assert_eq!(mock.calls(), vec![Call::Init, Call::GetSdkVersion, Call::Shutdown]);
+    assert!(!mock.is_initialized());
+}
+
+#[test]
+fn probe_reports_failed_init() {
+    let mock = MockBackend::new();
+    mock.fail_always(Operation::Init);
+
+    let probe = Driver::builder().backend(mock.clone()).probe();
+    assert!(probe.loaded);
+    assert!(!probe.is_supported());
+    match probe.error {
+        Some(Error::Init) => {},
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[cfg(not(windows))]
+#[test]
+fn unsupported_without_library() {
+    assert!(!led::is_supported());
+    assert!(Driver::probe().error.is_some());
+}
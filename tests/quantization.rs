@@ -0,0 +1,69 @@
+extern crate logitech_led as led;
+#[macro_use]
+extern crate quickcheck;
+
+use led::{Color, BGRA};
+use led::color::{self, Percent, PERCENT_ERROR, BYTE_ERROR};
+
+/// Map an arbitrary float into [0,1], so shrinking still finds small cases.
+fn unit(v: f32) -> f32 {
+    if v.is_finite() { v.abs().fract() } else { 0.0 }
+}
+
+fn color(r: f32, g: f32, b: f32) -> Color {
+    Color::new(unit(r), unit(g), unit(b))
+}
+
+fn max_error(a: Color, b: Color) -> f32 {
+    (a.r - b.r).abs().max((a.g - b.g).abs()).max((a.b - b.b).abs())
+}
+
+quickcheck! {
+    fn percent_round_trip(r: u8, g: u8, b: u8) -> bool {
+        let p = ((r % 101) as i32, (g % 101) as i32, (b % 101) as i32);
+        color::to_precent(color::from_precent(p)) == p
+    }
+
+    fn byte_round_trip(r: u8, g: u8, b: u8) -> bool {
+        let bgra: BGRA = [b, g, r, 255];
+        BGRA::from(Color::from(bgra)) == bgra
+    }
+
+    fn quantized_is_idempotent(r: f32, g: f32, b: f32) -> bool {
+        let q = color(r, g, b).quantized();
+        let qq = q.quantized();
+        q.r == qq.r && q.g == qq.g && q.b == qq.b
+    }
+
+    fn quantized_bytes_is_idempotent(r: f32, g: f32, b: f32) -> bool {
+        let q = color(r, g, b).quantized_bytes();
+        BGRA::from(q.quantized_bytes()) == BGRA::from(q)
+    }
+
+    fn percent_error_is_bounded(r: f32, g: f32, b: f32) -> bool {
+        let c = color(r, g, b);
+        max_error(c, c.quantized()) <= PERCENT_ERROR + 1e-6
+    }
+
+    fn byte_error_is_bounded(r: f32, g: f32, b: f32) -> bool {
+        let c = color(r, g, b);
+        max_error(c, c.quantized_bytes()) <= BYTE_ERROR + 1e-6
+    }
+}
+
+#[test]
+fn rounds_to_nearest() {
+    assert_eq!(color::to_precent(Color::new(0.994, 0.995, 0.004)), (99, 100, 0));
+    assert_eq!(BGRA::from(Color::new(0.5, 1.0 / 255.0 * 0.6, 0.0)), [0, 1, 128, 255]);
+    assert_eq!(Color::new(0.126, 0.5, 1.0).to_hex(), "#2080ff");
+}
+
+#[test]
+fn percent_range() {
+    assert_eq!(Percent::new(100), Some(Percent::FULL));
+    assert_eq!(Percent::new(101), None);
+    assert_eq!(Percent::from_f32(-1.0), Percent::ZERO);
+    assert_eq!(Percent::from_f32(2.0), Percent::FULL);
+    assert_eq!(Percent::from_f32(0.256).get(), 26);
+    assert_eq!(color::from_precent((-5, 50, 250)), Color::new(0.0, 0.5, 1.0));
+}
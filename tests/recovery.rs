@@ -0,0 +1,136 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Color, Key, DeviceType, Operation, RecoveryPolicy, Event, Health, Pacing, RetryPolicy};
+use led::mock::{MockBackend, Call};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn driver(mock: &MockBackend) -> (Driver, Arc<Mutex<Vec<Event>>>) {
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = events.clone();
+    driver.on_event(move |e| sink.lock().unwrap().push(e.clone()));
+    (driver, events)
+}
+
+#[test]
+fn detects_lost_ownership_after_threshold() {
+    let mock = MockBackend::new();
+    let (mut driver, events) = driver(&mock);
+    driver.set_recovery_policy(RecoveryPolicy::new(2));
+    mock.fail_always(Operation::SetLighting);
+
+    let red = Color::new(1.0, 0.0, 0.0);
+    assert!(driver.set_lighting(red).is_err());
+    assert!(events.lock().unwrap().is_empty());
+    assert!(driver.set_lighting(red).is_err());
+    assert_eq!(*events.lock().unwrap(), vec![Event::OwnershipLost { consecutive_failures: 2 }]);
+    assert_eq!(mock.count(Operation::Init), 1);
+}
+
+#[test]
+fn reinitializes_and_replays_state() {
+    let mock = MockBackend::new();
+    let (mut driver, events) = driver(&mock);
+    driver.set_recovery_policy(RecoveryPolicy::new(1).reinitialize(true));
+
+    let white = Color::new(1.0, 1.0, 1.0);
+    let bitmap = vec![0x80; led::BITMAP_SIZE];
+    driver.set_type(DeviceType::PERKEY_RGB).unwrap();
+    driver.exclude_keys_from_bitmap(&[Key::Q]).unwrap();
+    driver.set_lighting_from_bitmap(&bitmap).unwrap();
+    driver.set_lighting_for_key(Key::Q, white).unwrap();
+
+    mock.clear_calls();
+    mock.fail_next(Operation::SetLightingForKeyWithKeyName, 1);
+    driver.set_lighting_for_key(Key::W, white).unwrap();
+
+    assert_eq!(mock.calls(), vec![
+        Call::SetLightingForKey(Key::W, (100, 100, 100)),
+        Call::Init,
+        Call::SetTargetDevice(DeviceType::PERKEY_RGB),
+        Call::ExcludeKeysFromBitmap(vec![Key::Q]),
+        Call::SetLightingFromBitmap(bitmap),
+        Call::SetLightingForKey(Key::Q, (100, 100, 100)),
+        Call::SetTargetDevice(DeviceType::PERKEY_RGB),
+        Call::SetLightingForKey(Key::W, (100, 100, 100)),
+    ]);
+    assert_eq!(*events.lock().unwrap(), vec![
+        Event::OwnershipLost { consecutive_failures: 1 },
+        Event::Reinitialized { replayed: true },
+    ]);
+}
+
+#[test]
+fn replays_whole_device_lighting_per_target() {
+    let mock = MockBackend::new();
+    let (mut driver, _) = driver(&mock);
+
+    driver.set_type(DeviceType::RGB).unwrap();
+    driver.set_lighting(Color::new(1.0, 0.0, 0.0)).unwrap();
+    driver.set_type(DeviceType::MONOCHROME).unwrap();
+    driver.set_lighting(Color::new(0.5, 0.5, 0.5)).unwrap();
+
+    mock.clear_calls();
+    driver.reinitialize().unwrap();
+    assert_eq!(mock.calls(), vec![
+        Call::Init,
+        Call::SetTargetDevice(DeviceType::RGB),
+        Call::SetLighting((100, 0, 0)),
+        Call::SetTargetDevice(DeviceType::MONOCHROME),
+        Call::SetLighting((50, 50, 50)),
+        Call::SetTargetDevice(DeviceType::MONOCHROME),
+    ]);
+}
+
+#[test]
+fn replay_is_paced_and_retried() {
+    let mock = MockBackend::new();
+    let (mut driver, events) = driver(&mock);
+    let interval = Duration::from_millis(30);
+    driver.set_pacing(Pacing::new(interval));
+    driver.set_retry_policy(RetryPolicy::new(2));
+
+    let bitmap = vec![0x80; led::BITMAP_SIZE];
+    driver.set_type(DeviceType::PERKEY_RGB).unwrap();
+    driver.set_lighting_from_bitmap(&bitmap).unwrap();
+    driver.set_lighting_for_key(Key::Q, Color::new(1.0, 1.0, 1.0)).unwrap();
+
+    mock.clear_calls();
+    mock.fail_next(Operation::SetLightingFromBitmap, 1);
+    let retries = driver.metrics().retries;
+    let start = Instant::now();
+    driver.reinitialize().unwrap();
+
+    assert_eq!(mock.calls(), vec![
+        Call::Init,
+        Call::SetTargetDevice(DeviceType::PERKEY_RGB),
+        Call::SetLightingFromBitmap(bitmap.clone()),
+        Call::SetLightingFromBitmap(bitmap),
+        Call::SetLightingForKey(Key::Q, (100, 100, 100)),
+        Call::SetTargetDevice(DeviceType::PERKEY_RGB),
+    ]);
+    // Every call after `LogiLedInit` waited for the one before it.
+    assert!(start.elapsed() >= interval * 5, "{:?}", start.elapsed());
+    assert_eq!(driver.metrics().retries, retries + 1);
+    assert_eq!(events.lock().unwrap().last(), Some(&Event::Reinitialized { replayed: true }));
+}
+
+#[test]
+fn health_probe() {
+    let mock = MockBackend::new();
+    let (mut driver, events) = driver(&mock);
+    assert_eq!(driver.check_health(), Health::Healthy);
+
+    mock.fail_next(Operation::SetTargetDevice, 1);
+    assert_eq!(driver.check_health(), Health::Lost);
+
+    driver.set_recovery_policy(RecoveryPolicy::none().reinitialize(true));
+    mock.fail_next(Operation::SetTargetDevice, 1);
+    assert_eq!(driver.check_health(), Health::Recovered);
+
+    mock.fail_always(Operation::SetTargetDevice);
+    mock.fail_always(Operation::Init);
+    assert_eq!(driver.check_health(), Health::Lost);
+    assert_eq!(events.lock().unwrap().last(), Some(&Event::ReinitializeFailed));
+}
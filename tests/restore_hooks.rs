@@ -0,0 +1,122 @@
+//! The restore hooks are process wide, so every scenario runs in a child
+//! process: the parent re-runs this test binary with `CHILD_ENV` set to the
+//! scenario to run. The binary has no test harness, so a panic in a child
+//! really unwinds past `main`.
+
+extern crate logitech_led as led;
+
+use led::{Driver, Color, Key, DeviceType};
+use led::mock::MockBackend;
+use std::env;
+use std::process::{Command, Output};
+use std::thread;
+
+const CHILD_ENV: &str = "LOGITECH_LED_RESTORE_HOOKS_CHILD";
+
+fn run_child(name: &str) -> Output {
+    Command::new(env::current_exe().unwrap())
+        .env(CHILD_ENV, name)
+        .output()
+        .unwrap()
+}
+
+fn driver() -> Driver {
+    let mock = MockBackend::new();
+    mock.set_echo(true);
+    Driver::with_backend(mock).unwrap()
+}
+
+fn child_panic() {
+    let mut driver = driver();
+    driver.save_lighting().unwrap();
+    driver.set_lighting(Color::new(1.0, 0.0, 0.0)).unwrap();
+    driver.install_restore_hooks().unwrap();
+    eprintln!("child: panicking");
+    panic!("child panic");
+}
+
+fn panic_restores_saved_lighting() {
+    let output = run_child("panic");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+
+    let after = &stderr[stderr.find("child: panicking").expect(&stderr)..];
+    let restore = after.find("mock: RestoreLighting").expect(&stderr);
+    let shutdown = after.find("mock: Shutdown").expect(&stderr);
+    assert!(restore < shutdown);
+    assert!(after.contains("child panic"));
+    assert_eq!(after.matches("mock: Shutdown").count(), 1);
+}
+
+fn child_worker_panic() {
+    let mut driver = driver();
+    driver.save_lighting().unwrap();
+    driver.install_restore_hooks().unwrap();
+    eprintln!("child: spawning");
+    assert!(thread::spawn(|| panic!("worker panic")).join().is_err());
+    driver.set_lighting(Color::new(0.0, 1.0, 0.0)).unwrap();
+    eprintln!("child: done");
+}
+
+fn worker_panic_leaves_driver_running() {
+    let output = run_child("worker_panic");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+
+    let start = stderr.find("child: spawning").expect(&stderr);
+    let done = stderr.find("child: done").expect(&stderr);
+    let during = &stderr[start..done];
+    assert!(during.contains("worker panic"));
+    assert!(!during.contains("mock: RestoreLighting"), "{}", stderr);
+    assert!(!during.contains("mock: Shutdown"), "{}", stderr);
+}
+
+#[cfg(unix)]
+fn child_signal() {
+    let mut driver = driver();
+    driver.set_type(DeviceType::PERKEY_RGB).unwrap();
+    driver.set_lighting_for_key(Key::A, Color::new(0.0, 0.0, 1.0)).unwrap();
+    driver.save_snapshot();
+    driver.set_lighting_for_key(Key::A, Color::new(1.0, 1.0, 1.0)).unwrap();
+    driver.install_restore_hooks().unwrap();
+
+    eprintln!("child: signalling");
+    let pid = std::process::id().to_string();
+    Command::new("kill").args(["-TERM", &pid]).status().unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(10));
+    panic!("signal was not handled");
+}
+
+#[cfg(unix)]
+fn termination_signal_replays_snapshot() {
+    let output = run_child("signal");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(led::TERMINATE_EXIT_STATUS), "{}", stderr);
+
+    let after = &stderr[stderr.find("child: signalling").expect(&stderr)..];
+    let replay = after.find("mock: SetLightingForKey(A, (0, 0, 100))").expect(&stderr);
+    let shutdown = after.find("mock: Shutdown").expect(&stderr);
+    assert!(replay < shutdown);
+}
+
+fn main() {
+    match env::var(CHILD_ENV).ok().as_deref() {
+        Some("panic") => return child_panic(),
+        Some("worker_panic") => return child_worker_panic(),
+        #[cfg(unix)]
+        Some("signal") => return child_signal(),
+        Some(other) => panic!("unknown child {:?}", other),
+        None => {},
+    }
+
+    let mut tests: Vec<(&str, fn())> = vec![
+        ("panic_restores_saved_lighting", panic_restores_saved_lighting),
+        ("worker_panic_leaves_driver_running", worker_panic_leaves_driver_running),
+    ];
+    #[cfg(unix)]
+    tests.push(("termination_signal_replays_snapshot", termination_signal_replays_snapshot));
+    for (name, test) in tests {
+        test();
+        println!("test {} ... ok", name);
+    }
+}
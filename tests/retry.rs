@@ -0,0 +1,86 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Color, Key, Operation, RetryPolicy, Error};
+use led::mock::{MockBackend, Call};
+use std::time::Duration;
+
+fn driver(mock: &MockBackend) -> Driver {
+    Driver::with_backend(mock.clone()).unwrap()
+}
+
+#[test]
+fn no_retry_by_default() {
+    let mock = MockBackend::new();
+    let mut driver = driver(&mock);
+    mock.fail_next(Operation::SetLighting, 1);
+
+    match driver.set_lighting(Color::new(1.0, 0.0, 0.0)) {
+        Err(Error::SetLighting { .. }) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(mock.count(Operation::SetLighting), 1);
+    assert_eq!(driver.metrics().retries, 0);
+}
+
+#[test]
+fn retries_until_success() {
+    let mock = MockBackend::new();
+    let mut driver = driver(&mock);
+    driver.set_retry_policy(RetryPolicy::new(3));
+    mock.fail_next(Operation::SetLightingForKeyWithKeyName, 2);
+
+    driver.set_lighting_for_key(Key::W, Color::new(0.0, 1.0, 0.0)).unwrap();
+    assert_eq!(mock.count(Operation::SetLightingForKeyWithKeyName), 3);
+
+    let metrics = driver.metrics();
+    assert_eq!(metrics.retries, 2);
+    assert_eq!(metrics.failures, 2);
+    assert_eq!(metrics.exhausted, 0);
+}
+
+#[test]
+fn gives_up_after_attempts() {
+    let mock = MockBackend::new();
+    let mut driver = driver(&mock);
+    driver.set_retry_policy(RetryPolicy::new(3)
+        .backoff(Duration::from_millis(1), 2, Duration::from_millis(2)));
+    mock.fail_always(Operation::StopEffects);
+
+    let err = driver.stop_effects().unwrap_err();
+    assert!(err.is_retryable());
+    assert_eq!(mock.count(Operation::StopEffects), 3);
+    assert_eq!(driver.metrics().exhausted, 1);
+}
+
+#[test]
+fn only_selected_operations_are_retried() {
+    let mock = MockBackend::new();
+    let mut driver = driver(&mock);
+    driver.set_retry_policy(RetryPolicy::new(5).operations(&[Operation::SetLightingFromBitmap]));
+    mock.fail_next(Operation::SetLighting, 1);
+    mock.fail_next(Operation::SetLightingFromBitmap, 1);
+
+    assert!(driver.set_lighting(Color::new(0.0, 0.0, 1.0)).is_err());
+    driver.set_lighting_from_bitmap(&[0; led::BITMAP_SIZE]).unwrap();
+
+    assert_eq!(mock.count(Operation::SetLighting), 1);
+    assert_eq!(mock.count(Operation::SetLightingFromBitmap), 2);
+}
+
+#[test]
+fn backoff_is_capped() {
+    let policy = RetryPolicy::new(5)
+        .backoff(Duration::from_millis(10), 3, Duration::from_millis(50));
+    assert_eq!(policy.delay(1), Duration::from_millis(10));
+    assert_eq!(policy.delay(2), Duration::from_millis(30));
+    assert_eq!(policy.delay(3), Duration::from_millis(50));
+    assert_eq!(policy.delay(4), Duration::from_millis(50));
+}
+
+#[test]
+fn failed_init_does_not_shutdown() {
+    let mock = MockBackend::new();
+    mock.fail_next(Operation::Init, 1);
+    assert!(Driver::with_backend(mock.clone()).is_err());
+    assert_eq!(mock.calls(), vec![Call::Init]);
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "serde")]
+
+extern crate logitech_led as led;
+extern crate serde_json;
+
+use led::{Color, Direction, Gradient};
+use led::effect::builtin::*;
+use std::time::Duration;
+
+#[test]
+fn effects_round_trip() {
+    let scroll = GradientScroll {
+        gradient: Gradient::rainbow(),
+        direction: Direction::Angle(45.0),
+        period: Duration::from_millis(1500),
+    };
+    let json = serde_json::to_string(&scroll).unwrap();
+    assert_eq!(serde_json::from_str::<GradientScroll>(&json).unwrap(), scroll);
+
+    let stars = Starlight { seed: 42, ..Starlight::new(Color::new(0.5, 0.25, 1.0)) };
+    let json = serde_json::to_string(&stars).unwrap();
+    assert_eq!(serde_json::from_str::<Starlight>(&json).unwrap(), stars);
+}
+
+#[test]
+fn settings_from_json() {
+    let json = r#"{
+        "color": { "r": 1.0, "g": 0.0, "b": 0.0 },
+        "width": 3.0,
+        "period": { "secs": 1, "nanos": 500000000 }
+    }"#;
+    let scanner: Scanner = serde_json::from_str(json).unwrap();
+    assert_eq!(scanner, Scanner { color: Color::RED, width: 3.0, period: Duration::from_millis(1500) });
+}
+
+#[test]
+fn gradient_stops_are_sorted_and_clamped() {
+    let json = r#"{
+        "stops": [
+            [1.5, { "r": 0.0, "g": 0.0, "b": 1.0 }],
+            [0.0, { "r": 1.0, "g": 0.0, "b": 0.0 }],
+            [0.5, { "r": 0.0, "g": 1.0, "b": 0.0 }]
+        ]
+    }"#;
+    let g: Gradient = serde_json::from_str(json).unwrap();
+    assert_eq!(g, Gradient::new(&[(0.0, Color::RED), (0.5, Color::LIME), (1.0, Color::BLUE)]));
+    assert_eq!(g.sample(0.25), Color::new(0.5, 0.5, 0.0));
+}
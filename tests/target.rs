@@ -0,0 +1,23 @@
+extern crate logitech_led as led;
+
+use led::{Driver, DeviceType, Color, Key};
+use led::target::{PerKeyRgb, Monochrome};
+use led::mock::{MockBackend, Call};
+
+#[test]
+fn target_sets_device_type() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    mock.clear_calls();
+
+    driver.target::<PerKeyRgb>().unwrap().set_lighting_for_key(Key::W, Color::new(1.0, 0.0, 0.0)).unwrap();
+    driver.target::<Monochrome>().unwrap().set_lighting(Color::new(0.5, 0.5, 0.5)).unwrap();
+
+    assert_eq!(mock.calls(), vec![
+        Call::SetTargetDevice(DeviceType::PERKEY_RGB),
+        Call::SetLightingForKey(Key::W, (100, 0, 0)),
+        Call::SetTargetDevice(DeviceType::MONOCHROME),
+        Call::SetLighting((50, 50, 50)),
+    ]);
+    assert_eq!(driver.lighting_state().target, DeviceType::MONOCHROME);
+}
@@ -0,0 +1,54 @@
+extern crate logitech_led as led;
+
+use led::{Driver, Color, Zone, ZoneTarget, Operation, Error};
+use led::mock::{MockBackend, Call, MOCK_SDK_VERSION};
+
+#[test]
+fn set_lighting_for_zone() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    let zone = Zone::new(ZoneTarget::Headset, 1);
+
+    driver.set_lighting_for_zone(zone, Color::new(0.0, 1.0, 0.0)).unwrap();
+    assert_eq!(mock.calls().last(), Some(&Call::SetLightingForTargetZone(ZoneTarget::Headset, 1, (0, 100, 0))));
+    assert_eq!(driver.lighting_state().zones.get(&zone), Some(&(0, 100, 0)));
+    assert_eq!(driver.sdk_version().unwrap(), MOCK_SDK_VERSION);
+}
+
+#[test]
+fn set_zones_from_bitmap_on_newer_sdk() {
+    let mock = MockBackend::new();
+    let mut driver = Driver::with_backend(mock.clone()).unwrap();
+    assert!(driver.supports(Operation::SetLightingFromBitmapForTargetDevice));
+
+    // BGRA, one entry per zone.
+    let bitmap = [0x00, 0x00, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0xFF];
+    driver.set_lighting_from_bitmap_for_target(ZoneTarget::Mouse, &bitmap).unwrap();
+    assert_eq!(mock.calls().last(),
+        Some(&Call::SetLightingFromBitmapForTargetDevice(ZoneTarget::Mouse, bitmap.to_vec())));
+    assert_eq!(driver.lighting_state().zones.get(&Zone::new(ZoneTarget::Mouse, 0)), Some(&(100, 0, 0)));
+    assert_eq!(driver.lighting_state().zones.get(&Zone::new(ZoneTarget::Mouse, 1)), Some(&(0, 0, 100)));
+}
+
+#[test]
+fn optional_functions_on_older_sdk() {
+    let mock = MockBackend::new();
+    mock.unsupport(Operation::InitWithName);
+    mock.unsupport(Operation::GetSdkVersion);
+    mock.unsupport(Operation::SetLightingForTargetZone);
+    mock.unsupport(Operation::SetLightingFromBitmapForTargetDevice);
+
+    let mut driver = Driver::builder().backend(mock.clone()).app_name("test").build().unwrap();
+    assert_eq!(mock.calls(), vec![Call::Init]);
+
+    match driver.set_lighting_for_zone(Zone::new(ZoneTarget::Mouse, 0), Color::new(1.0, 1.0, 1.0)) {
+        Err(Error::Unsupported(Operation::SetLightingForTargetZone)) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+    match driver.set_lighting_from_bitmap_for_target(ZoneTarget::Headset, &[0xFF; 8]) {
+        Err(Error::Unsupported(Operation::SetLightingFromBitmapForTargetDevice)) => {},
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(mock.calls(), vec![Call::Init]);
+    assert!(!driver.sdk_version().unwrap_err().is_retryable());
+}